@@ -5,11 +5,17 @@ use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use crate::assetsdb_lib::e_asset::Asset;
 use crate::mesh_lib::c_mesh::Mesh;
+use crate::render_lib::c_background::BackgroundSettings;
+use crate::render_lib::c_particle_effect::ParticleEffect;
+use crate::assetsdb_lib::loaders::c_background_asset_processor::BackgroundLoader;
 use crate::assetsdb_lib::loaders::c_config_asset_processor::ConfigLoader;
+use crate::assetsdb_lib::loaders::c_font_asset_processor::FontLoader;
 use crate::assetsdb_lib::loaders::c_meshes_asset_processor::MeshLoader;
+use crate::assetsdb_lib::loaders::c_particles_asset_processor::ParticlesLoader;
 use crate::assetsdb_lib::loaders::c_sprite_asset_processor::ImageLoader;
 use crate::assetsdb_lib::loaders::t_asset_loader::AssetLoader;
 use crate::assetsdb_lib::t_from_assetref::FromAssetRef;
+use crate::font_lib::c_stroke_font::StrokeFont;
 use crate::sprite_lib::c_sprite::SpriteTex;
 
 pub struct AssetsDB {
@@ -73,7 +79,10 @@ impl AssetsDB {
         self.loaders = vec![
             Box::new(MeshLoader::default()),
             Box::new(ConfigLoader::default()),
-            Box::new(ImageLoader::default())
+            Box::new(ImageLoader::default()),
+            Box::new(FontLoader::default()),
+            Box::new(ParticlesLoader::default()),
+            Box::new(BackgroundLoader::default())
         ];
 
         for loader in self.loaders.iter() {
@@ -143,6 +152,51 @@ impl AssetsDB {
         })
     }
 
+    /// Every loaded mesh with the file it came from, sorted by path.
+    pub fn get_meshes(&self) -> Vec<(PathBuf, Rc<Mesh>)> {
+        let mut meshes: Vec<(PathBuf, Rc<Mesh>)> = self.map.iter()
+            .filter_map(|(k, v)| match v {
+                Asset::Mesh(m) => Some((PathBuf::from(k), Rc::clone(m))),
+                _ => None,
+            })
+            .collect();
+        meshes.sort_by(|a, b| a.0.cmp(&b.0));
+        meshes
+    }
+
+    pub fn get_font_by_name(&self, name: &str) -> Option<Rc<StrokeFont>> {
+        self.map.iter().find_map(|(k, v)| match v {
+            Asset::Font(f)
+
+            if Path::new(k)
+                .file_stem()
+                .and_then(|s| s.to_str()) == Some(name) => Some(Rc::clone(f)),
+            _ => None,
+        })
+    }
+
+    pub fn get_particles_by_name(&self, name: &str) -> Option<Rc<ParticleEffect>> {
+        self.map.iter().find_map(|(k, v)| match v {
+            Asset::Particles(p)
+
+            if Path::new(k)
+                .file_stem()
+                .and_then(|s| s.to_str()) == Some(name) => Some(Rc::clone(p)),
+            _ => None,
+        })
+    }
+
+    pub fn get_background_by_name(&self, name: &str) -> Option<Rc<BackgroundSettings>> {
+        self.map.iter().find_map(|(k, v)| match v {
+            Asset::Background(b)
+
+            if Path::new(k)
+                .file_stem()
+                .and_then(|s| s.to_str()) == Some(name) => Some(Rc::clone(b)),
+            _ => None,
+        })
+    }
+
     pub fn get_all_assets_by_type<T: FromAssetRef>(&self) -> Option<Vec<&T>> {
         let mut list: Vec<&T> = vec![];
 
@@ -1,10 +1,16 @@
 ﻿use std::rc::Rc;
 use crate::config_lib::c_config::Config;
+use crate::font_lib::c_stroke_font::StrokeFont;
 use crate::mesh_lib::c_mesh::Mesh;
+use crate::render_lib::c_background::BackgroundSettings;
+use crate::render_lib::c_particle_effect::ParticleEffect;
 use crate::sprite_lib::c_sprite::SpriteTex;
 
 pub enum Asset {
     Mesh(Rc<Mesh>),
     Config(Config),
     Sprite(Rc<SpriteTex>),
+    Font(Rc<StrokeFont>),
+    Particles(Rc<ParticleEffect>),
+    Background(Rc<BackgroundSettings>),
 }
\ No newline at end of file
@@ -1,7 +1,8 @@
 ﻿use std::fs::File;
-use std::io::BufReader;
+use std::io::{BufReader, BufWriter, Write};
 use std::path::Path;
 use serde::de::DeserializeOwned;
+use serde::Serialize;
 
 #[derive(Debug)]
 pub enum ReadJsonError {
@@ -21,4 +22,11 @@ pub fn read_json_file<T: DeserializeOwned>(path: &impl AsRef<Path>) -> Result<T,
     let reader = BufReader::new(file);
     let val = serde_json::from_reader(reader)?;
     Ok(val)
+}
+
+pub fn write_json_file<T: Serialize>(path: &impl AsRef<Path>, value: &T) -> std::io::Result<()> {
+    let file = File::create(path.as_ref())?;
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer(&mut writer, value)?;
+    writer.flush()
 }
\ No newline at end of file
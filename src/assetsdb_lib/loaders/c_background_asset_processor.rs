@@ -0,0 +1,35 @@
+﻿use crate::assetsdb_lib::e_asset::Asset;
+use crate::assetsdb_lib::loaders::t_asset_loader::AssetLoader;
+use crate::assetsdb_lib::t_file_readable::FileReadable;
+use crate::render_lib::c_background::BackgroundSettings;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+#[derive(Default)]
+pub struct BackgroundLoader{}
+
+impl AssetLoader for BackgroundLoader{
+    fn get_extension(&self) -> String {
+        "bg".to_string()
+    }
+
+    fn load_all_assets(&self, files: &Vec<PathBuf>) -> HashMap<String, Asset> {
+        let mut data: HashMap<String, Asset> = HashMap::new();
+
+        for file in files {
+            match file.extension(){
+                Some(ext) => {
+                    if ext.to_str().unwrap() == self.get_extension() {
+                        let mut background = BackgroundSettings::default();
+                        if background.read_file(file.clone()) {
+                            data.insert(file.to_str().unwrap().to_string(), Asset::Background(Rc::new(background)));
+                        }
+                    }
+                }
+                None => {continue;}
+            }
+        }
+        data
+    }
+}
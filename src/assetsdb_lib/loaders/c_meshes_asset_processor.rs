@@ -2,6 +2,7 @@
 use crate::assetsdb_lib::loaders::t_asset_loader::AssetLoader;
 use crate::assetsdb_lib::t_file_readable::FileReadable;
 use crate::mesh_lib::c_mesh::Mesh;
+use crate::mesh_lib::c_svg_import::import_svg_file;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::rc::Rc;
@@ -26,6 +27,14 @@ impl AssetLoader for MeshLoader{
                             data.insert(file.to_str().unwrap().to_string(), Asset::Mesh(Rc::new(mesh)));
                         }
                     }
+                    if ext.to_str() == Some("svg") {
+                        match import_svg_file(file) {
+                            Ok(mesh) => {
+                                data.insert(file.to_str().unwrap().to_string(), Asset::Mesh(Rc::new(mesh)));
+                            }
+                            Err(e) => println!("Error importing svg {}: {}", file.to_str().unwrap(), e),
+                        }
+                    }
                 }
                 None => {continue;}
             }
@@ -39,3 +48,19 @@ impl MeshLoader {
         "mesh".to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_mesh_with_bad_vertex_index() {
+        let path = std::env::temp_dir().join(format!("asteroids_bad_{}.mesh", std::process::id()));
+        std::fs::write(&path, r#"{"version":2,"name":"bad","filled":false,"vertices":[[0.0,0.0]],"polylines":[{"indices":[0,3]}]}"#).unwrap();
+
+        let assets = MeshLoader::default().load_all_assets(&vec![path.clone()]);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(assets.is_empty());
+    }
+}
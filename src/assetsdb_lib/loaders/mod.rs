@@ -1,4 +1,7 @@
 ﻿pub mod c_meshes_asset_processor;
 pub mod t_asset_loader;
 pub mod c_config_asset_processor;
-pub mod c_sprite_asset_processor;
\ No newline at end of file
+pub mod c_sprite_asset_processor;
+pub mod c_font_asset_processor;
+pub mod c_particles_asset_processor;
+pub mod c_background_asset_processor;
\ No newline at end of file
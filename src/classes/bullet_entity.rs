@@ -21,9 +21,16 @@ pub struct BulletEntity {
 
     timer: f32,
     col_layers: ColLayer,
+    /// Local player that fired it, `None` for enemy shots.
+    owner: Option<usize>,
+    is_hitted: bool,
 }
 
 impl Drawable for BulletEntity {
+    fn get_z_order(&self) -> i32 {
+        10
+    }
+
     fn draw(&mut self, screen: &mut Screen) {
         self.draw_mesh(screen, &self.transform, &self.mesh);
     }
@@ -38,8 +45,13 @@ impl Collide for BulletEntity {
         Some((self.mesh.clone(), &self.transform))
     }
 
+    fn get_owner(&self) -> Option<usize> {
+        self.owner
+    }
+
     fn on_collision(&mut self, layer: ColLayer) {
         self.timer = 1000.0;
+        self.is_hitted = true;
     }
 }
 
@@ -60,10 +72,20 @@ impl Entity for BulletEntity {
         self.timer += delta_time;
 
         if (self.timer > 0.5){
-            
-            return vec![
+            let mut events = vec![
                 DestroyEntity(self.id),
-            ]
+            ];
+
+            if self.is_hitted && let Some(effect) = assets_db.get_particles_by_name("sparks") {
+                events.push(SceneEvent::EmitParticles {
+                    count: effect.burst_count(),
+                    effect,
+                    pos: *self.transform.get_position(),
+                    dir: -vel,
+                    velocity: Vec2::zero(),
+                });
+            }
+            return events;
         }
         vec![]
     }
@@ -74,7 +96,7 @@ impl Entity for BulletEntity {
 }
 
 impl BulletEntity {
-    pub fn new(transform: Transform, mesh: Rc<Mesh>, start_speed: f32, col: ColLayer) -> BulletEntity {
+    pub fn new(transform: Transform, mesh: Rc<Mesh>, start_speed: f32, col: ColLayer, owner: Option<usize>) -> BulletEntity {
 
 
         BulletEntity {
@@ -84,6 +106,8 @@ impl BulletEntity {
             speed: start_speed + 2000.0,
             timer: 0.0,
             col_layers: col,
+            owner,
+            is_hitted: false,
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,137 @@
+﻿use std::f32::consts::{PI, TAU};
+use vek::Vec2;
+use crate::classes::c_virtual_device::{VirtualDevice, VirtualEvent};
+use crate::classes::e_action::{Action, Axis};
+use crate::classes::t_entity::Entity;
+use crate::collisions_lib::e_col_layers::ColLayer;
+
+/// Player shots fly at about this speed, used to lead moving targets.
+const BULLET_SPEED: f32 = 600.0;
+/// Shots expire after about this distance.
+const FIRE_RANGE: f32 = 320.0;
+/// Fires when the nose is within this angle of the aim point.
+const FIRE_ANGLE: f32 = 0.12;
+/// Closes in on targets farther away than this.
+const APPROACH_DISTANCE: f32 = 260.0;
+/// A rock this close and closing in triggers a hyperspace jump.
+const PANIC_DISTANCE: f32 = 55.0;
+
+/// What the pilot does this frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PilotCommand {
+    pub rotate: f32,
+    pub thrust: f32,
+    pub fire: bool,
+    pub hyperspace: bool,
+}
+
+/// Computer player for the attract-mode demo. It turns towards the nearest asteroid or UFO,
+/// aiming where the target will be when the shot arrives, fires once lined up, closes in on far
+/// targets and jumps to hyperspace when a rock is about to hit. It plays through a `VirtualDevice`,
+/// so the ship cannot tell it from a person.
+pub struct AiPilot {
+    device: VirtualDevice,
+    command: PilotCommand,
+}
+
+impl AiPilot {
+    pub fn new(device: VirtualDevice) -> Self {
+        Self { device, command: PilotCommand::default() }
+    }
+
+    /// Looks at the field and feeds this frame's decision into the device.
+    pub fn steer(&mut self, entities: &[Box<dyn Entity>]) {
+        let ship = entities.iter()
+            .filter(|e| e.get_collision_layer() == ColLayer::Player)
+            .find_map(|e| e.get_collision_mesh().map(|(_, t)| (*e.get_position(), t.get_rotation())));
+        let targets: Vec<(Vec2<f32>, Vec2<f32>)> = entities.iter()
+            .filter(|e| matches!(e.get_collision_layer(), ColLayer::Asteroid | ColLayer::Enemy))
+            .map(|e| (*e.get_position(), e.get_velocity()))
+            .collect();
+
+        let command = match ship {
+            Some((pos, rotation)) => decide(pos, rotation, &targets),
+            None => PilotCommand::default(),
+        };
+        self.apply(command);
+    }
+
+    fn apply(&mut self, command: PilotCommand) {
+        let device = &self.device;
+        device.schedule(0.0, VirtualEvent::SetAxis(Axis::Rotate, command.rotate));
+        device.schedule(0.0, VirtualEvent::SetAxis(Axis::Thrust, command.thrust));
+
+        for (action, now, before) in [
+            (Action::Fire, command.fire, self.command.fire),
+            (Action::Hyperspace, command.hyperspace, self.command.hyperspace),
+        ] {
+            if now && !before {
+                device.schedule(0.0, VirtualEvent::Press(action));
+            } else if !now && before {
+                device.schedule(0.0, VirtualEvent::Release(action));
+            }
+        }
+        self.command = command;
+    }
+}
+
+/// Steering for a ship at `pos` facing `rotation` (radians) among `(position, velocity)` targets.
+pub fn decide(pos: Vec2<f32>, rotation: f32, targets: &[(Vec2<f32>, Vec2<f32>)]) -> PilotCommand {
+    let Some((target, velocity)) = targets.iter()
+        .min_by(|a, b| pos.distance_squared(a.0).total_cmp(&pos.distance_squared(b.0)))
+        .copied() else {
+        return PilotCommand::default();
+    };
+
+    let distance = pos.distance(target);
+    let aim = target + velocity * (distance / BULLET_SPEED);
+    let to_aim = aim - pos;
+    let error = wrap_angle(to_aim.y.atan2(to_aim.x) - rotation);
+
+    // closing in when the gap shrinks along the line between ship and rock
+    let closing = velocity.dot(pos - target) > 0.0;
+
+    PilotCommand {
+        rotate: (error * 3.0).clamp(-1.0, 1.0),
+        thrust: if distance > APPROACH_DISTANCE && error.abs() < 0.5 { 0.5 } else { 0.0 },
+        fire: error.abs() < FIRE_ANGLE && distance < FIRE_RANGE,
+        hyperspace: distance < PANIC_DISTANCE && closing,
+    }
+}
+
+/// Maps an angle into `-PI..PI`.
+fn wrap_angle(angle: f32) -> f32 {
+    (angle + PI).rem_euclid(TAU) - PI
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turns_towards_and_fires_at_the_nearest_rock() {
+        let ship = Vec2::new(100.0, 100.0);
+        let near_below = (Vec2::new(100.0, 250.0), Vec2::zero());
+        let far_right = (Vec2::new(600.0, 100.0), Vec2::zero());
+
+        // facing right, the nearest rock is below: turn clockwise (positive) and hold fire
+        let command = decide(ship, 0.0, &[far_right, near_below]);
+        assert_eq!(command.rotate, 1.0);
+        assert!(!command.fire);
+
+        let command = decide(ship, PI / 2.0, &[far_right, near_below]);
+        assert!(command.rotate.abs() < 0.01);
+        assert!(command.fire);
+        assert_eq!(command.thrust, 0.0);
+    }
+
+    #[test]
+    fn jumps_away_from_an_incoming_rock() {
+        let ship = Vec2::new(100.0, 100.0);
+        let incoming = (Vec2::new(140.0, 100.0), Vec2::new(-80.0, 0.0));
+        let leaving = (Vec2::new(140.0, 100.0), Vec2::new(80.0, 0.0));
+
+        assert!(decide(ship, 0.0, &[incoming]).hyperspace);
+        assert!(!decide(ship, 0.0, &[leaving]).hyperspace);
+    }
+}
@@ -14,10 +14,17 @@ use winit::{
     window::WindowBuilder,
 };
 use winit::dpi::LogicalSize;
-use winit::event::ElementState;
+use winit::event::{ElementState, MouseScrollDelta};
 use winit::keyboard::PhysicalKey;
-use winit::window::{Icon, Window, WindowButtons};
+use winit::keyboard::KeyCode;
+use winit::window::{Fullscreen, Icon, Window};
 use crate::classes::c_input::Input;
+use crate::config_lib::c_config::KEYBOARD_PLAYERS;
+use crate::render_lib::c_frame_recorder::{capture_path, save_screenshot, FrameRecorder};
+use crate::render_lib::c_viewport::Viewport;
+
+/// Touchpads report scrolling in pixels, wheels in lines.
+const PIXELS_PER_LINE: f32 = 40.0;
 
 #[derive(Copy, Clone, Default)]
 pub struct AppHandler {
@@ -28,19 +35,16 @@ pub struct AppHandler {
 
 impl AppHandler {
     pub fn run(&mut self, game: &mut Game, input: &mut Input) {
-        let config = game.get_config();
-
-        let fb_w = config.x().max(1) as u32;
-        let fb_h = config.y().max(1) as u32;
+        let config = game.get_config().clone();
 
         let event_loop = EventLoop::new().unwrap();
 
         let window = Arc::new(
             WindowBuilder::new()
                 .with_title("Asteroids")
-                .with_resizable(false)
+                .with_resizable(true)
                 .with_inner_size(LogicalSize::new(config.x() as u32, config.y() as u32))
-                .with_enabled_buttons(WindowButtons::CLOSE | WindowButtons::MINIMIZE)
+                .with_fullscreen(fullscreen_mode(config.is_fullscreen()))
                 .build(&event_loop)
                 .unwrap()
         );
@@ -49,10 +53,14 @@ impl AppHandler {
 
         
         let win_size = window.inner_size(); // Physical Pixels
+        let fb_w = win_size.width.max(1);
+        let fb_h = win_size.height.max(1);
 
-        game.get_config_mut().set_actual_size(Vec2::new(win_size.width as usize, win_size.height as usize));
+        game.get_config_mut().set_actual_size(Vec2::new(fb_w as usize, fb_h as usize));
 
-        let surface = SurfaceTexture::new(win_size.width.max(1), win_size.height.max(1), window.clone());
+        // The pixels buffer matches the surface 1:1, the playfield is letterboxed into it by the viewport
+        let mut viewport = Viewport::new(config.size(), Vec2::new(fb_w as usize, fb_h as usize));
+        let surface = SurfaceTexture::new(fb_w, fb_h, window.clone());
 
         let mut pixels = Pixels::new(fb_w, fb_h, surface).unwrap();
         pixels.enable_vsync(false);
@@ -60,8 +68,21 @@ impl AppHandler {
         let egui_ctx = egui::Context::default();
         let viewport_id = egui_ctx.viewport_id();
         setup_egui_fonts(&egui_ctx);
+        egui_ctx.set_zoom_factor(ui_zoom(&viewport, window.scale_factor()));
 
         game.get_assets_db_mut().load_dynamic(&egui_ctx);
+        for player in 0..KEYBOARD_PLAYERS {
+            if let Some(bindings) = config.bindings(player) {
+                input.set_player_bindings(player, bindings.clone());
+            }
+        }
+        #[cfg(feature = "gamepad")]
+        for (player, gamepad) in crate::classes::c_gamepad_backend::GamepadBackend::for_players(config.gamepad(), crate::classes::c_input::MAX_PLAYERS)
+            .into_iter()
+            .flatten()
+            .enumerate() {
+            input.add_player_backend(player, Box::new(gamepad));
+        }
         game.open_default_scene();
         
         let mut egui_state = EguiWinitState::new(
@@ -82,6 +103,9 @@ impl AppHandler {
         event_loop.set_control_flow(ControlFlow::Poll);
 
 
+        let mut recorder: Option<FrameRecorder> = None;
+        let mut screenshot_requested = false;
+
         let mut last = Instant::now();
         event_loop.run(move |event, elwt| match event {
 
@@ -93,9 +117,52 @@ impl AppHandler {
                         let is_down = event.state == ElementState::Pressed;
 
                         if let PhysicalKey::Code(code) = event.physical_key {
+                            if code == KeyCode::F11 && is_down && !event.repeat {
+                                let fullscreen = window.fullscreen().is_none();
+                                window.set_fullscreen(fullscreen_mode(fullscreen));
+                            }
+
+                            if code == KeyCode::F12 && is_down && !event.repeat {
+                                screenshot_requested = true;
+                            }
+
+                            if code == KeyCode::F10 && is_down && !event.repeat {
+                                match recorder.take() {
+                                    Some(active) => {
+                                        let path = active.get_path().to_path_buf();
+                                        match active.finish() {
+                                            Ok(count) => println!("recorded {count} frames to {}", path.display()),
+                                            Err(e) => eprintln!("recording failed: {e}"),
+                                        }
+                                    }
+                                    None => {
+                                        recorder = Some(FrameRecorder::new(capture_path("recording", "")));
+                                    }
+                                }
+                            }
+
                             input.on_key(code, is_down);
                         }
                     }
+                    WindowEvent::CursorMoved { position, .. } => {
+                        input.on_cursor_moved(viewport.physical_to_logical(Vec2::new(position.x as f32, position.y as f32)));
+                    }
+                    WindowEvent::CursorLeft { .. } => {
+                        input.on_cursor_moved(None);
+                    }
+                    WindowEvent::MouseInput { state, button, .. } => {
+                        // presses over egui widgets belong to the UI, releases always go through
+                        let is_down = *state == ElementState::Pressed;
+                        if !is_down || !consumed {
+                            input.on_mouse_button(*button, is_down);
+                        }
+                    }
+                    WindowEvent::MouseWheel { delta, .. } if !consumed => {
+                        input.on_mouse_wheel(match delta {
+                            MouseScrollDelta::LineDelta(x, y) => Vec2::new(*x, *y),
+                            MouseScrollDelta::PixelDelta(p) => Vec2::new(p.x as f32, p.y as f32) / PIXELS_PER_LINE,
+                        });
+                    }
                     WindowEvent::Resized(size) => {
                         let w = size.width.max(1);
                         let h = size.height.max(1);
@@ -103,11 +170,13 @@ impl AppHandler {
                         pixels.resize_surface(w, h).unwrap();
                         pixels.resize_buffer(w, h).unwrap();
 
-                        game.get_screen_mut().resize(w, h);
+                        viewport.resize(Vec2::new(w as usize, h as usize));
+                        game.get_config_mut().set_actual_size(Vec2::new(w as usize, h as usize));
+                        egui_state.egui_ctx().set_zoom_factor(ui_zoom(&viewport, window.scale_factor()));
                     }
 
                     WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
-                        egui_state.egui_ctx().set_pixels_per_point(*scale_factor as f32);
+                        egui_state.egui_ctx().set_zoom_factor(ui_zoom(&viewport, *scale_factor));
                     }
                     WindowEvent::CloseRequested => { elwt.exit(); }
                     WindowEvent::RedrawRequested => {
@@ -124,10 +193,23 @@ impl AppHandler {
                         {
                             ///LOGIC
                             input.update(dt);
-                            if (!game.update_game(dt, &egui_state.egui_ctx(), &input)) {
+                            if !game.update_game(dt, egui_state.egui_ctx(), input) {
                                 elwt.exit();
                             }
-                            blit_u32_to_rgba_bytes(pixels.frame_mut(), game.get_screen().get_buffer());
+                            input.end_frame();
+                            viewport.blit(pixels.frame_mut(), game.get_screen());
+
+                            if let Some(recorder) = &recorder {
+                                recorder.push_frame(game.get_screen(), (dt * 1000.0).round() as u32);
+                            }
+                            if screenshot_requested {
+                                screenshot_requested = false;
+                                let path = capture_path("screenshot", "png");
+                                match save_screenshot(game.get_screen(), &path) {
+                                    Ok(_) => println!("screenshot saved to {}", path.display()),
+                                    Err(e) => eprintln!("screenshot failed: {e}"),
+                                }
+                            }
                         }
 
                         let full = egui_state.egui_ctx().end_frame();
@@ -209,18 +291,19 @@ impl AppHandler {
     }
 }
 
-fn blit_u32_to_rgba_bytes(dst_rgba: &mut [u8], src_u32: &[u32]) {
-    for (dst, &p) in dst_rgba.chunks_exact_mut(4).zip(src_u32.iter()) {
-        let r = ((p >> 16) & 0xFF) as u8;
-        let g = ((p >> 8) & 0xFF) as u8;
-        let b = (p & 0xFF) as u8;
-        dst[0] = r;
-        dst[1] = g;
-        dst[2] = b;
-        dst[3] = 0xFF;
+fn fullscreen_mode(fullscreen: bool) -> Option<Fullscreen> {
+    if fullscreen {
+        Some(Fullscreen::Borderless(None))
+    } else {
+        None
     }
 }
 
+/// egui zoom so that one UI point equals one playfield pixel on any window size and DPI.
+fn ui_zoom(viewport: &Viewport, scale_factor: f64) -> f32 {
+    (viewport.scale() / scale_factor as f32).max(0.1)
+}
+
 fn load_window_icon(path: impl AsRef<Path>) -> Result<Icon, Box<dyn Error>> {
     let img = image::open(path)?.into_rgba8();
     let (w, h) = img.dimensions();
@@ -5,13 +5,14 @@ use crate::assetsdb_lib::c_assets_db::AssetsDB;
 use crate::classes::c_input::Input;
 use crate::classes::t_entity::Entity;
 use crate::collisions_lib::e_col_layers::ColLayer;
-use crate::collisions_lib::t_collision::Collide;
+use crate::collisions_lib::t_collision::{Collide, Contact};
 use crate::config_lib::c_config::Config;
 use crate::mesh_lib::c_mesh::Mesh;
 use crate::render_lib::t_drawable::Drawable;
 use crate::render_lib::t_screen_data::Screen;
 use crate::scenes_lib::e_scene_event::SceneEvent;
 use crate::transform_lib::c_transform::Transform;
+use crate::random_lib::c_sim_rng::sim_rng;
 
 pub struct AsteroidEntity {
     id: u32,
@@ -19,6 +20,10 @@ pub struct AsteroidEntity {
     mesh: Rc<Mesh>,
 
     rotation: f32,
+    /// Area-preserving scale, fragments of a fractured parent keep the parent transform scale.
+    size: f32,
+    impact: Option<Vec2<f32>>,
+    hit_by: Option<usize>,
     is_need_destroy: bool
 }
 
@@ -29,13 +34,16 @@ impl AsteroidEntity {
     pub fn new(transform: Transform, mesh: Rc<Mesh>) -> AsteroidEntity {
         
         
-        let mut rnd = rand::rng();
-        
+        let mut rnd = sim_rng();
+
         Self{
             id: 0,
             transform,
             mesh,
             rotation: rnd.random_range(-1.0..1.0)*0.5,
+            size: transform.get_scale().magnitude(),
+            impact: None,
+            hit_by: None,
             is_need_destroy: false,
             
         }
@@ -44,6 +52,10 @@ impl AsteroidEntity {
     pub fn set_velocity(&mut self, dir: Vec2<f32>) {
         self.transform.set_velocity(dir);
     }
+
+    pub fn set_size(&mut self, size: f32) {
+        self.size = size;
+    }
 }
 
 impl Drawable for AsteroidEntity {
@@ -61,9 +73,11 @@ impl Collide for AsteroidEntity {
         Some((self.mesh.clone(), &self.transform))
     }
 
-    fn on_collision(&mut self, layer: ColLayer) {
-        if (layer == ColLayer::BulletPlayer || layer == ColLayer::BulletEnemy) {
+    fn on_contact(&mut self, contact: &Contact) {
+        if contact.layer == ColLayer::BulletPlayer || contact.layer == ColLayer::BulletEnemy {
             self.is_need_destroy = true;
+            self.impact = Some(contact.position);
+            self.hit_by = contact.owner;
         }
     }
 }
@@ -76,10 +90,15 @@ impl Entity for AsteroidEntity {
         self.transform.update_position_by_vel(delta_time);
 
         if (self.is_need_destroy) {
+            let pos = *self.transform.get_position();
             events.push(SceneEvent::DemolishAsteroid{
-                pos: *self.transform.get_position(),
-                scale: self.transform.get_scale().magnitude(),
+                pos,
+                scale: self.size,
                 id: self.id,
+                mesh: self.mesh.clone(),
+                transform: self.transform,
+                impact: self.impact.unwrap_or(pos),
+                by: self.hit_by,
             });
             events.push(SceneEvent::DestroyEntity(self.id));
         }
@@ -0,0 +1,40 @@
+﻿use crate::assetsdb_lib::c_assets_db::AssetsDB;
+use crate::classes::c_ai_pilot::AiPilot;
+use crate::classes::c_input::Input;
+use crate::classes::c_scene_game::GameScene;
+use crate::classes::c_virtual_device::VirtualDevice;
+use crate::config_lib::c_config::Config;
+use crate::render_lib::t_screen_data::Screen;
+use crate::scenes_lib::t_scene::Scene;
+
+/// A silent solo game flown by an `AiPilot`, played behind the main menu. It has its own
+/// `Input`, so the menu's keys never reach the demo ship, and starts over after a game over.
+pub struct AttractDemo {
+    scene: GameScene,
+    input: Input,
+    pilot: AiPilot,
+}
+
+impl AttractDemo {
+    pub fn new(config: &Config, screen: &Screen, assets_db: &AssetsDB) -> Self {
+        let device = VirtualDevice::default();
+        let mut input = Input::default();
+        input.add_backend(Box::new(device.clone()));
+
+        let mut scene = GameScene::new_demo();
+        scene.create_scene(config, screen, assets_db);
+
+        Self { scene, input, pilot: AiPilot::new(device) }
+    }
+
+    pub fn update(&mut self, dt: f32, config: &Config, assets_db: &AssetsDB) {
+        self.pilot.steer(self.scene.get_entities());
+        self.input.update(dt);
+        self.scene.update(dt, &self.input, config, assets_db);
+        self.input.end_frame();
+    }
+
+    pub fn render(&mut self, screen: &mut Screen) {
+        self.scene.render(screen);
+    }
+}
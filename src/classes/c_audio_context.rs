@@ -8,11 +8,17 @@ pub struct AudioContext {
 }
 
 impl AudioContext {
+    /// Falls back to a silent context when there is no audio device (headless runs, CI).
     pub fn new() -> Self {
-        let stream = rodio::OutputStreamBuilder::open_default_stream()
-            .expect("open default audio stream");
+        let stream = match rodio::OutputStreamBuilder::open_default_stream() {
+            Ok(stream) => Some(stream),
+            Err(e) => {
+                eprintln!("audio disabled: {e}");
+                None
+            }
+        };
         Self {
-            stream: Some(stream)
+            stream
         }
     }
 
@@ -14,6 +14,7 @@ use crate::render_lib::t_screen_data::Screen;
 use crate::scenes_lib::e_scene_event::SceneEvent;
 use crate::scenes_lib::e_scene_event::SceneEvent::DestroyEntity;
 use crate::transform_lib::c_transform::Transform;
+use crate::random_lib::c_sim_rng::sim_rng;
 
 pub struct DebrisEntity{
     id: u32,
@@ -29,6 +30,10 @@ pub struct DebrisEntity{
 
 
 impl Drawable for DebrisEntity {
+    fn get_z_order(&self) -> i32 {
+        -10
+    }
+
     fn draw(&mut self, screen: &mut Screen) {
         
         self.draw_mesh(screen, &self.transform, &self.mesh);
@@ -79,7 +84,7 @@ impl Entity for DebrisEntity {
 impl DebrisEntity {
     pub fn new(transform: Transform, mesh: Rc<Mesh>) -> DebrisEntity {
 
-        let mut rng = rand::rng();
+        let mut rng = sim_rng();
 
         let dir = transform.transform_dir_to_world(Vec2::new(1.0, 0.0));
 
@@ -93,4 +98,9 @@ impl DebrisEntity {
             rot_dir: rng.random_range(-1.0..1.0),
         }
     }
+
+    pub fn set_velocity(&mut self, velocity: Vec2<f32>) {
+        self.speed = velocity.magnitude();
+        self.dir = velocity.try_normalized().unwrap_or_default();
+    }
 }
\ No newline at end of file
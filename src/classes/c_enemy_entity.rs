@@ -15,6 +15,7 @@ use crate::render_lib::t_screen_data::Screen;
 use crate::scenes_lib::e_scene_event::SceneEvent;
 use crate::scenes_lib::e_scene_event::SceneEvent::SpawnEntity;
 use crate::transform_lib::c_transform::Transform;
+use crate::random_lib::c_sim_rng::sim_rng;
 
 pub struct EnemyEntity{
     id: u32,
@@ -35,7 +36,7 @@ impl EnemyEntity {
 
     pub fn new(transform: Transform, mesh: Rc<Mesh>) -> EnemyEntity {
 
-        let mut rnd = rand::rng();
+        let mut rnd = sim_rng();
 
         let mut dir = 0;
 
@@ -58,7 +59,7 @@ impl EnemyEntity {
     fn spawn_bullet(&mut self, events: &mut Vec<SceneEvent>, assets_db: &AssetsDB, config: &Config) {
         let mesh = assets_db.get_mesh_by_name("bullet");
         let spawn_point = self.transform.transform_point_to_world(Vec2::new(50.0, 0.0));
-        let mut rnd = rand::rng();
+        let mut rnd = sim_rng();
 
         let rot = rnd.random_range(-360.0..360.0);
 
@@ -71,7 +72,8 @@ impl EnemyEntity {
             ),
             mesh.unwrap_or_default(),
             500.0,
-            ColLayer::BulletEnemy
+            ColLayer::BulletEnemy,
+            None
         );
 
         events.push(SceneEvent::Shoot(self.get_collision_layer()));
@@ -80,6 +82,10 @@ impl EnemyEntity {
 }
 
 impl Drawable for EnemyEntity {
+    fn get_z_order(&self) -> i32 {
+        20
+    }
+
     fn draw(&mut self, screen: &mut Screen) {
         self.draw_mesh(screen, &self.transform, &self.mesh);
     }
@@ -1,9 +1,16 @@
 ﻿use crate::assetsdb_lib::c_assets_db::AssetsDB;
 use crate::classes::c_input::Input;
 use crate::classes::c_scene_game::GameScene;
+use crate::classes::c_scene_editor::EditorScene;
 use crate::classes::c_scene_menu::MenuScene;
+use crate::classes::c_scene_pause::PauseScene;
 use crate::classes::c_scene_test::TestScene;
+use crate::classes::c_input::MAX_PLAYERS;
+use crate::classes::e_game_mode::GameMode;
 use crate::config_lib::c_config::Config;
+use crate::net_lib::c_lockstep::{LockstepSession, SessionState};
+use crate::net_lib::c_net_game::NetGame;
+use crate::render_lib::c_transition::{Transition, TransitionPlayer};
 use crate::render_lib::t_screen_data::Screen;
 use crate::scenes_lib::e_scene_switch::SceneSwitch;
 use crate::scenes_lib::e_sceneid::SceneId;
@@ -14,8 +21,15 @@ pub struct Game{
     config: Config,
     assets_db: AssetsDB,
 
-    scene: Box<dyn Scene>
-
+    /// Open scenes with the id each was built from, the last one is on top and the only one
+    /// that updates. Never empty.
+    scenes: Vec<(SceneId, Box<dyn Scene>)>,
+    /// Online match driving the scene, `None` for local play.
+    net: Option<NetGame>,
+    /// Why the last online match ended, shown over the menu until dismissed.
+    net_notice: Option<String>,
+    /// Effect over the first frames after a scene change, the new scene already runs beneath it.
+    transition: Option<TransitionPlayer>,
 }
 impl Game {
     pub fn new() -> Self {
@@ -23,38 +37,175 @@ impl Game {
         let assets_db = AssetsDB::new();
 
 
-        let config = assets_db.get_any_asset_by_type::<Config>().cloned().unwrap_or_default();
+        let mut config = assets_db.get_any_asset_by_type::<Config>().cloned().unwrap_or_default();
+        config.load_user_settings(&Config::user_settings_path());
+        config.fill_missing_bindings();
 
-        let screen = Screen::new(config.x(), config.y());
+        let mut screen = Screen::new(config.x(), config.y());
+        screen.set_raster_threads(config.raster_threads());
         Self{
             assets_db,
             config,
             screen,
-            scene: Box::new(MenuScene::default()),
+            scenes: vec![(SceneId::Menu, Box::new(MenuScene::default()))],
+            net: None,
+            net_notice: None,
+            transition: None,
         }
     }
 
     pub fn open_default_scene(&mut self){
+        self.open_scene(SceneId::Menu);
+    }
 
-        let scene = make_scene(SceneId::Menu, &self.config, &self.screen, &self.assets_db);
-        self.scene = scene;
+    /// Closes every open scene and starts over with `scene_id`.
+    pub fn open_scene(&mut self, scene_id: SceneId){
+        self.leave_lockstep();
+        let scene = make_scene(scene_id, &self.config, &self.screen, &self.assets_db);
+        self.scenes = vec![(scene_id, scene)];
     }
 
+    pub fn push_scene(&mut self, scene_id: SceneId) {
+        let scene = make_scene(scene_id, &self.config, &self.screen, &self.assets_db);
+        self.scenes.push((scene_id, scene));
+    }
 
-    pub fn update_game(&mut self, delta_time: f32, ctx: &egui::Context, input: &Input) -> bool {
-        self.screen.flush();
+    /// The bottom scene stays, popping it would leave nothing to show.
+    pub fn pop_scene(&mut self) {
+        if self.scenes.len() > 1 {
+            self.scenes.pop();
+        }
+    }
+
+    pub fn replace_scene(&mut self, scene_id: SceneId) {
+        self.scenes.pop();
+        self.push_scene(scene_id);
+    }
+
+    /// Pops the top scene and rebuilds the one that comes to the top.
+    pub fn restart_scene(&mut self) {
+        self.pop_scene();
+        if let Some((scene_id, _)) = self.scenes.pop() {
+            self.push_scene(scene_id);
+        }
+    }
+
+    fn top_scene(&mut self) -> &mut Box<dyn Scene> {
+        &mut self.scenes.last_mut().expect("scene stack is never empty").1
+    }
+
+    /// Starts the online match of a connected session, the scene is seeded from the session.
+    pub fn start_lockstep(&mut self, session: LockstepSession) {
+        self.leave_lockstep();
+        let net = NetGame::new(session);
+
+        let mode = net.get_session().get_mode();
+        let mut scene = GameScene::new_lockstep(mode);
+        scene.create_scene(&self.config, &self.screen, &self.assets_db);
+        self.scenes = vec![(SceneId::for_mode(mode), Box::new(scene))];
+        self.net = Some(net);
+    }
+
+    fn leave_lockstep(&mut self) {
+        if let Some(mut net) = self.net.take() {
+            net.get_session_mut().close();
+        }
+    }
 
-        let scene_event = self.scene.update(delta_time, input, &self.config, &self.assets_db);
+    /// Back to the menu when the peer left or the simulations diverged.
+    fn check_lockstep(&mut self) {
+        let Some(net) = &self.net else {
+            return;
+        };
+        let notice = match net.get_session().get_state() {
+            SessionState::Running => return,
+            SessionState::Desync(tick) => format!("Desync at tick {tick}, the games no longer match."),
+            SessionState::Disconnected(reason) => format!("Online match ended: {reason}."),
+        };
+        self.net_notice = Some(notice);
+        self.open_scene(SceneId::Menu);
+    }
+
+    pub fn get_net(&self) -> Option<&NetGame> {
+        self.net.as_ref()
+    }
+
+    pub fn get_net_mut(&mut self) -> Option<&mut NetGame> {
+        self.net.as_mut()
+    }
+
+    pub fn take_net_notice(&mut self) -> Option<String> {
+        self.net_notice.take()
+    }
+
+
+    pub fn update_game(&mut self, delta_time: f32, ctx: &egui::Context, input: &mut Input) -> bool {
+        let scene = &mut self.scenes.last_mut().expect("scene stack is never empty").1;
+        let scene_event = match &mut self.net {
+            Some(net) => net.update(delta_time, input.player(0), scene.as_mut(), &self.config, &self.assets_db),
+            None => scene.update(delta_time, input, &self.config, &self.assets_db),
+        };
         if let Some(value) = self.match_event(scene_event) {
             return value;
         }
-        self.scene.render(&mut self.screen);
-        let ui_event = self.scene.ui(ctx);
+        self.check_lockstep();
+
+        // the screen still holds the last frame until here, a transition started above captured it
+        self.screen.flush();
+
+        // overlays show the scenes below them, from the last one that draws the whole screen up
+        let first = self.scenes.iter().rposition(|(_, s)| !s.draws_below()).unwrap_or(0);
+        for (_, scene) in self.scenes[first..].iter_mut() {
+            scene.render(&mut self.screen);
+        }
+        self.screen.rasterize();
+        if let Some(transition) = &mut self.transition {
+            transition.apply(&mut self.screen);
+            transition.advance(delta_time);
+            if transition.is_finished() {
+                self.transition = None;
+            }
+        }
+        let ui_event = self.top_scene().ui(ctx);
 
         if let Some(value) = self.match_event(ui_event) {
             return value;
         }
 
+        if let Some(session) = self.top_scene().take_lockstep() {
+            self.start_lockstep(session);
+        }
+
+        if let Some(notice) = &self.net_notice {
+            let mut open = true;
+            egui::Window::new("Online")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+                .show(ctx, |ui| {
+                    ui.label(notice.as_str());
+                    if ui.button("OK").clicked() {
+                        open = false;
+                    }
+                });
+            if !open {
+                self.net_notice = None;
+            }
+        }
+
+        if let Some(bindings) = self.top_scene().take_bindings() {
+            for (player, b) in bindings.into_iter().enumerate() {
+                if let Some(slot) = self.config.bindings_mut(player) {
+                    *slot = b;
+                    input.set_player_bindings(player, slot.clone());
+                }
+            }
+            let path = Config::user_settings_path();
+            if let Err(e) = self.config.save_user_settings(&path) {
+                println!("Error saving user settings {}: {}", path.display(), e);
+            }
+        }
+
         //
         // egui::Window::new("Debug").show(ctx, |ui| {
         //     ui.label(format!("dt: {:.4} sec", delta_time));
@@ -66,12 +217,22 @@ impl Game {
     }
 
     fn match_event(&mut self, scene_event: SceneSwitch) -> Option<bool> {
+        let transition = scene_event.transition();
+        if transition != Transition::Cut {
+            self.transition = Some(TransitionPlayer::new(transition, &self.screen));
+        }
+
         match scene_event {
             SceneSwitch::None => {}
-            SceneSwitch::Switch(new_scene_id) => {
-                self.scene = make_scene(new_scene_id, &self.config, &self.screen, &self.assets_db);
+            SceneSwitch::Switch(new_scene_id) | SceneSwitch::SwitchWith(new_scene_id, _) => {
+                self.open_scene(new_scene_id);
             }
+            SceneSwitch::Push(scene_id) => self.push_scene(scene_id),
+            SceneSwitch::Pop => self.pop_scene(),
+            SceneSwitch::Replace(scene_id) => self.replace_scene(scene_id),
+            SceneSwitch::Restart => self.restart_scene(),
             SceneSwitch::Quit => {
+                self.leave_lockstep();
                 return Some(false);
             }
         };
@@ -83,9 +244,6 @@ impl Game {
         &self.screen
     }
 
-    pub fn get_screen_mut(&mut self) -> &mut Screen {
-        &mut self.screen
-    }
     pub fn get_config(&self) -> &Config {
         &self.config
     }
@@ -102,19 +260,82 @@ impl Game {
         &mut self.assets_db
     }
 
+    /// The scene on top of the stack.
     pub fn get_current_scene(&self) -> &Box<dyn Scene> {
-        &self.scene
+        &self.scenes.last().expect("scene stack is never empty").1
     }
 }
 
 fn make_scene(scene_id: SceneId, config: &Config, screen: &Screen, assets_db: &AssetsDB) -> Box<dyn Scene> {
     let mut value : Box<dyn Scene> = match scene_id {
         SceneId::Menu => Box::new(MenuScene::default()),
-        SceneId::Game => Box::new(GameScene::default()),
-        SceneId::Test => Box::new(TestScene::default())
+        SceneId::Game => Box::new(GameScene::new(GameMode::Solo)),
+        SceneId::Coop => Box::new(GameScene::new(GameMode::Coop)),
+        SceneId::Versus(players) => Box::new(GameScene::new(GameMode::Versus { players: players.clamp(2, MAX_PLAYERS) })),
+        SceneId::Pause => Box::new(PauseScene::default()),
+        SceneId::Settings => Box::new(MenuScene::settings()),
+        SceneId::Test => Box::new(TestScene::default()),
+        SceneId::Editor => Box::new(EditorScene::default())
     };
     
     value.create_scene(config, screen, assets_db);
     
     return value;
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random_lib::c_sim_rng::seed_sim_rng;
+
+    fn stack(game: &Game) -> Vec<String> {
+        game.scenes.iter().map(|(_, s)| s.get_scene_name()).collect()
+    }
+
+    #[test]
+    fn push_pop_and_replace_work_on_the_top_scene() {
+        let mut game = Game::new();
+        game.open_scene(SceneId::Test);
+
+        game.push_scene(SceneId::Pause);
+        assert_eq!(stack(&game), ["TestScene", "PauseScene"]);
+
+        game.replace_scene(SceneId::Settings);
+        assert_eq!(stack(&game), ["TestScene", "MenuScene"]);
+
+        game.pop_scene();
+        assert_eq!(stack(&game), ["TestScene"]);
+    }
+
+    #[test]
+    fn pop_never_empties_the_stack() {
+        let mut game = Game::new();
+        game.open_scene(SceneId::Test);
+
+        game.pop_scene();
+        game.match_event(SceneSwitch::Pop);
+        assert_eq!(stack(&game), ["TestScene"]);
+    }
+
+    #[test]
+    fn restart_rebuilds_the_scene_under_the_pause_menu() {
+        let mut game = Game::new();
+        let input = Input::default();
+
+        seed_sim_rng(7);
+        game.open_scene(SceneId::Game);
+        let fresh = game.get_current_scene().state_checksum();
+
+        let (_, scene) = game.scenes.last_mut().unwrap();
+        for _ in 0..30 {
+            scene.update(1.0 / 60.0, &input, &game.config, &game.assets_db);
+        }
+        assert_ne!(game.get_current_scene().state_checksum(), fresh);
+
+        game.push_scene(SceneId::Pause);
+        seed_sim_rng(7);
+        game.restart_scene();
+
+        assert_eq!(stack(&game), ["GameScene"]);
+        assert_eq!(game.get_current_scene().state_checksum(), fresh);
+    }
+}
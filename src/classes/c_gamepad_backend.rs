@@ -0,0 +1,115 @@
+﻿use std::cell::RefCell;
+use std::rc::Rc;
+use gilrs::{Button, GamepadId, Gilrs};
+use crate::classes::c_gamepad_settings::{apply_deadzone, GamepadSettings, PadButton};
+use crate::classes::e_action::{Action, Axis};
+use crate::classes::t_input_backend::InputBackend;
+
+/// Gamepad input through gilrs for one player: the n-th connected pad drives player n.
+/// All backends share one `Gilrs`, a pad plugged in later joins on the next `poll`.
+pub struct GamepadBackend {
+    gilrs: Rc<RefCell<Gilrs>>,
+    settings: GamepadSettings,
+    slot: usize,
+    pad: Option<GamepadId>,
+}
+
+impl GamepadBackend {
+    /// A backend for each of the first `players` players, `None` when gilrs cannot start.
+    pub fn for_players(settings: &GamepadSettings, players: usize) -> Option<Vec<GamepadBackend>> {
+        match Gilrs::new() {
+            Ok(gilrs) => {
+                let gilrs = Rc::new(RefCell::new(gilrs));
+                let backends = (0..players)
+                    .map(|slot| GamepadBackend { gilrs: gilrs.clone(), settings: settings.clone(), slot, pad: None })
+                    .collect();
+                Some(backends)
+            }
+            Err(e) => {
+                eprintln!("gamepad disabled: {e}");
+                None
+            }
+        }
+    }
+
+    fn is_button_down(&self, button: PadButton) -> bool {
+        let Some(id) = self.pad else {
+            return false;
+        };
+        self.gilrs.borrow().gamepad(id).is_pressed(to_gilrs(button))
+    }
+
+    fn button_value(&self, button: Button) -> f32 {
+        self.pad
+            .and_then(|id| self.gilrs.borrow().gamepad(id).button_data(button).map(|d| d.value()))
+            .unwrap_or(0.0)
+    }
+
+    fn stick_value(&self, axis: gilrs::Axis) -> f32 {
+        self.pad
+            .map(|id| self.gilrs.borrow().gamepad(id).value(axis))
+            .unwrap_or(0.0)
+    }
+}
+
+fn to_gilrs(button: PadButton) -> Button {
+    match button {
+        PadButton::South => Button::South,
+        PadButton::East => Button::East,
+        PadButton::North => Button::North,
+        PadButton::West => Button::West,
+        PadButton::LeftBumper => Button::LeftTrigger,
+        PadButton::RightBumper => Button::RightTrigger,
+        PadButton::LeftTrigger => Button::LeftTrigger2,
+        PadButton::RightTrigger => Button::RightTrigger2,
+        PadButton::Select => Button::Select,
+        PadButton::Start => Button::Start,
+        PadButton::DPadUp => Button::DPadUp,
+        PadButton::DPadDown => Button::DPadDown,
+        PadButton::DPadLeft => Button::DPadLeft,
+        PadButton::DPadRight => Button::DPadRight,
+    }
+}
+
+impl InputBackend for GamepadBackend {
+    fn poll(&mut self, _dt: f32) {
+        let mut gilrs = self.gilrs.borrow_mut();
+        // the first backend to poll drains the queue, gilrs keeps the state of every pad
+        while gilrs.next_event().is_some() {}
+        self.pad = gilrs.gamepads().map(|(id, _)| id).nth(self.slot);
+    }
+
+    fn is_connected(&self) -> bool {
+        self.pad.is_some()
+    }
+
+    fn is_action_down(&self, action: Action) -> bool {
+        let threshold = self.settings.press_threshold;
+        let analog = match action {
+            Action::RotateLeft => self.get_axis(Axis::Rotate) <= -threshold,
+            Action::RotateRight => self.get_axis(Axis::Rotate) >= threshold,
+            Action::Thrust => self.get_axis(Axis::Thrust) >= threshold,
+            Action::Reverse => self.get_axis(Axis::Thrust) <= -threshold,
+            Action::MenuUp => self.stick_value(gilrs::Axis::LeftStickY) >= threshold,
+            Action::MenuDown => self.stick_value(gilrs::Axis::LeftStickY) <= -threshold,
+            Action::MenuLeft => self.stick_value(gilrs::Axis::LeftStickX) <= -threshold,
+            Action::MenuRight => self.stick_value(gilrs::Axis::LeftStickX) >= threshold,
+            _ => false,
+        };
+        analog || self.settings.buttons(action).iter().any(|b| self.is_button_down(*b))
+    }
+
+    fn get_axis(&self, axis: Axis) -> f32 {
+        let stick = self.settings.stick_deadzone;
+        let trigger = self.settings.trigger_deadzone;
+        match axis {
+            Axis::Rotate => apply_deadzone(self.stick_value(gilrs::Axis::LeftStickX), stick),
+            Axis::Thrust => {
+                let stick_y = apply_deadzone(self.stick_value(gilrs::Axis::LeftStickY), stick);
+                let forward = apply_deadzone(self.button_value(Button::RightTrigger2), trigger);
+                let reverse = apply_deadzone(self.button_value(Button::LeftTrigger2), trigger);
+                (stick_y + forward - reverse).clamp(-1.0, 1.0)
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+﻿use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use crate::classes::e_action::Action;
+
+/// Gamepad buttons by position, independent of the gamepad library so configs load without the feature.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftBumper,
+    RightBumper,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+/// Gamepad tuning and button bindings, stored in the config under `gamepad`.
+/// The left stick rotates (x) and thrusts (y), the right trigger thrusts and the left trigger reverses.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct GamepadSettings {
+    /// Stick values below this magnitude read as zero, the rest is rescaled to `0..1`.
+    pub stick_deadzone: f32,
+    pub trigger_deadzone: f32,
+    /// Analog value past which an axis also counts as its digital action, e.g. `Thrust`.
+    pub press_threshold: f32,
+    pub buttons: BTreeMap<Action, Vec<PadButton>>,
+}
+
+impl Default for GamepadSettings {
+    fn default() -> Self {
+        let buttons = [
+            (Action::RotateLeft, vec![PadButton::DPadLeft]),
+            (Action::RotateRight, vec![PadButton::DPadRight]),
+            (Action::Thrust, vec![PadButton::DPadUp]),
+            (Action::Reverse, vec![PadButton::DPadDown]),
+            (Action::Fire, vec![PadButton::South, PadButton::RightBumper]),
+            (Action::Hyperspace, vec![PadButton::West, PadButton::LeftBumper]),
+            (Action::Pause, vec![PadButton::Start]),
+            (Action::MenuUp, vec![PadButton::DPadUp]),
+            (Action::MenuDown, vec![PadButton::DPadDown]),
+            (Action::MenuLeft, vec![PadButton::DPadLeft]),
+            (Action::MenuRight, vec![PadButton::DPadRight]),
+            (Action::MenuConfirm, vec![PadButton::South]),
+            (Action::MenuBack, vec![PadButton::East]),
+        ].into_iter().collect();
+
+        Self {
+            stick_deadzone: 0.2,
+            trigger_deadzone: 0.1,
+            press_threshold: 0.5,
+            buttons,
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "gamepad"), allow(dead_code))]
+impl GamepadSettings {
+    pub fn buttons(&self, action: Action) -> &[PadButton] {
+        self.buttons.get(&action).map(|b| b.as_slice()).unwrap_or(&[])
+    }
+}
+
+/// Zeroes values inside `deadzone` and rescales the rest so the output still spans `-1..=1`.
+#[cfg_attr(not(feature = "gamepad"), allow(dead_code))]
+pub fn apply_deadzone(value: f32, deadzone: f32) -> f32 {
+    let magnitude = value.abs();
+    if magnitude <= deadzone || deadzone >= 1.0 {
+        return 0.0;
+    }
+    value.signum() * ((magnitude - deadzone) / (1.0 - deadzone)).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deadzone_zeroes_and_rescales() {
+        assert_eq!(apply_deadzone(0.15, 0.2), 0.0);
+        assert_eq!(apply_deadzone(-0.2, 0.2), 0.0);
+        assert!((apply_deadzone(0.6, 0.2) - 0.5).abs() < 1e-6);
+        assert!((apply_deadzone(-1.0, 0.2) + 1.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,318 @@
+﻿use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use egui::{pos2, vec2, RawInput, Rect};
+use crate::classes::c_game::Game;
+use crate::classes::c_input::{Input, MAX_PLAYERS};
+use crate::classes::c_virtual_device::VirtualDevice;
+use crate::classes::e_game_mode::GameMode;
+use crate::config_lib::c_config::KEYBOARD_PLAYERS;
+use crate::net_lib::c_lobby::Lobby;
+use crate::net_lib::c_lockstep::LockstepSession;
+use crate::render_lib::c_frame_recorder::{save_screenshot, FrameRecorder};
+use crate::scenes_lib::e_sceneid::SceneId;
+
+/// Command line switches for a run without a window:
+/// `--headless [--scene game] [--frames 300] [--dt 0.016] [--screenshot out.png] [--record out.gif|dir] [--input Thrust@0-1,Fire@0.5] [--input-p2 ...]`
+/// and for an online match `[--host 7777 [--mode coop|versus] | --join 127.0.0.1:7777]`, where `--frames` counts lockstep ticks.
+#[derive(Clone, Debug)]
+pub struct HeadlessOptions {
+    pub scene: SceneId,
+    pub frames: u32,
+    pub delta_time: f32,
+    pub screenshot: Option<PathBuf>,
+    pub record: Option<PathBuf>,
+    /// Scripted actions fed through a `VirtualDevice`, see `VirtualDevice::from_script`,
+    /// with the local player they drive (`--input` is the first, `--input-p2` the second, ...).
+    pub inputs: Vec<(usize, String)>,
+    /// Waits for a peer on this port instead of opening `scene`.
+    pub host: Option<u16>,
+    /// Address of a hosting peer.
+    pub join: Option<String>,
+    pub online_mode: GameMode,
+}
+
+impl Default for HeadlessOptions {
+    fn default() -> Self {
+        Self {
+            scene: SceneId::Game,
+            frames: 300,
+            delta_time: 1.0 / 60.0,
+            screenshot: None,
+            record: None,
+            inputs: Vec::new(),
+            host: None,
+            join: None,
+            online_mode: GameMode::Coop,
+        }
+    }
+}
+
+impl HeadlessOptions {
+    /// `Ok(None)` when `--headless` is not present, `Err` describes the first bad argument.
+    pub fn from_args(args: impl Iterator<Item = String>) -> Result<Option<HeadlessOptions>, String> {
+        let mut options = HeadlessOptions::default();
+        let mut headless = false;
+
+        let mut args = args.skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--headless" => headless = true,
+                "--scene" => {
+                    let name = next_value(&mut args, &arg)?;
+                    options.scene = SceneId::from_name(&name).ok_or_else(|| format!("unknown scene '{name}'"))?;
+                }
+                "--frames" => options.frames = parse_value(&mut args, &arg)?,
+                "--dt" => options.delta_time = parse_value(&mut args, &arg)?,
+                "--screenshot" => options.screenshot = Some(PathBuf::from(next_value(&mut args, &arg)?)),
+                "--record" => options.record = Some(PathBuf::from(next_value(&mut args, &arg)?)),
+                "--input" => options.inputs.push((0, next_value(&mut args, &arg)?)),
+                "--host" => options.host = Some(parse_value(&mut args, &arg)?),
+                "--join" => options.join = Some(next_value(&mut args, &arg)?),
+                "--mode" => {
+                    options.online_mode = match next_value(&mut args, &arg)?.as_str() {
+                        "coop" => GameMode::Coop,
+                        "versus" => GameMode::Versus { players: 2 },
+                        other => return Err(format!("unknown mode '{other}'")),
+                    };
+                }
+                _ => match arg.strip_prefix("--input-p").and_then(|n| n.parse::<usize>().ok()) {
+                    Some(player) if (1..=MAX_PLAYERS).contains(&player) => {
+                        options.inputs.push((player - 1, next_value(&mut args, &arg)?));
+                    }
+                    _ => return Err(format!("unknown argument '{arg}'")),
+                },
+            }
+        }
+
+        Ok(if headless { Some(options) } else { None })
+    }
+}
+
+fn next_value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    args.next().ok_or_else(|| format!("{flag} needs a value"))
+}
+
+fn parse_value<T: FromStr>(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<T, String> {
+    let value = next_value(args, flag)?;
+    value.parse().map_err(|_| format!("invalid value '{value}' for {flag}"))
+}
+
+/// Seconds to wait for the other peer before giving up on an online run.
+const CONNECT_TIMEOUT: f32 = 30.0;
+/// Seconds a finished peer keeps answering so the other one can finish too.
+const LINGER_TIME: f32 = 0.5;
+
+/// Runs the game loop with a fixed time step and no window, for clips and render baselines.
+pub struct HeadlessHandler {
+    options: HeadlessOptions,
+}
+
+impl HeadlessHandler {
+    pub fn new(options: HeadlessOptions) -> HeadlessHandler {
+        HeadlessHandler { options }
+    }
+
+    pub fn run(&mut self, game: &mut Game, input: &mut Input) {
+        let config = game.get_config().clone();
+        let egui_ctx = egui::Context::default();
+
+        game.get_assets_db_mut().load_dynamic(&egui_ctx);
+        for player in 0..KEYBOARD_PLAYERS {
+            if let Some(bindings) = config.bindings(player) {
+                input.set_player_bindings(player, bindings.clone());
+            }
+        }
+        for (player, script) in &self.options.inputs {
+            match VirtualDevice::from_script(script) {
+                Ok(device) => input.add_player_backend(*player, Box::new(device)),
+                Err(e) => eprintln!("bad --input script: {e}"),
+            }
+        }
+
+        if self.options.host.is_some() || self.options.join.is_some() {
+            self.run_online(game, input, &egui_ctx);
+            return;
+        }
+        game.open_scene(self.options.scene);
+
+        let recorder = self.options.record.clone().map(FrameRecorder::new);
+        let dt = self.options.delta_time;
+        let frame_ms = (dt * 1000.0).round() as u32;
+
+        for _ in 0..self.options.frames {
+            egui_ctx.begin_frame(RawInput {
+                screen_rect: Some(Rect::from_min_size(pos2(0.0, 0.0), vec2(config.x() as f32, config.y() as f32))),
+                ..Default::default()
+            });
+
+            input.update(dt);
+            let running = game.update_game(dt, &egui_ctx, input);
+            input.end_frame();
+            let _ = egui_ctx.end_frame();
+
+            if let Some(recorder) = &recorder {
+                recorder.push_frame(game.get_screen(), frame_ms);
+            }
+
+            if !running {
+                break;
+            }
+        }
+
+        self.save_screenshot(game);
+
+        if let Some(recorder) = recorder {
+            let path = recorder.get_path().to_path_buf();
+            match recorder.finish() {
+                Ok(count) => println!("recorded {count} frames to {}", path.display()),
+                Err(e) => eprintln!("recording failed: {e}"),
+            }
+        }
+    }
+
+    fn save_screenshot(&self, game: &Game) {
+        if let Some(path) = &self.options.screenshot {
+            match save_screenshot(game.get_screen(), path) {
+                Ok(_) => println!("screenshot saved to {}", path.display()),
+                Err(e) => eprintln!("screenshot failed: {e}"),
+            }
+        }
+    }
+
+    /// Blocks until the lobby hands over a session.
+    fn connect(&self, game: &Game) -> Option<LockstepSession> {
+        let playfield = game.get_config().size();
+        let lobby = match (&self.options.join, self.options.host) {
+            (Some(address), _) => Lobby::join(address, playfield),
+            (None, Some(port)) => Lobby::host(port, self.options.online_mode, playfield),
+            (None, None) => return None,
+        };
+        let mut lobby = match lobby {
+            Ok(lobby) => lobby,
+            Err(e) => {
+                eprintln!("online: {e}");
+                return None;
+            }
+        };
+        println!("{}", lobby.describe());
+
+        let step = 0.01;
+        let mut waited = 0.0;
+        while waited < CONNECT_TIMEOUT {
+            lobby.poll(step);
+            if let Some(session) = lobby.take_session() {
+                return Some(session);
+            }
+            if let Some(error) = lobby.get_error() {
+                eprintln!("online: {error}");
+                return None;
+            }
+            std::thread::sleep(Duration::from_secs_f32(step));
+            waited += step;
+        }
+        eprintln!("online: nobody connected");
+        None
+    }
+
+    /// Plays `frames` lockstep ticks against the peer as fast as both ends allow, then prints
+    /// the verified checksums so two runs can be compared.
+    fn run_online(&mut self, game: &mut Game, input: &mut Input, egui_ctx: &egui::Context) {
+        let Some(session) = self.connect(game) else {
+            return;
+        };
+        println!("connected as player {}, seed {}", session.get_local_player() + 1, session.get_seed());
+
+        let config = game.get_config().clone();
+        let ticks = self.options.frames;
+        game.start_lockstep(session);
+        if let Some(net) = game.get_net_mut() {
+            net.set_tick_limit(Some(ticks));
+        }
+
+        let dt = self.options.delta_time;
+        let mut finished_at: Option<Instant> = None;
+        let mut last = (0, 0);
+        while let Some(net) = game.get_net() {
+            last = (net.get_tick(), net.get_session().get_verified());
+            if net.get_tick() >= ticks && net.get_session().is_settled() {
+                let at = *finished_at.get_or_insert_with(Instant::now);
+                if at.elapsed().as_secs_f32() > LINGER_TIME {
+                    break;
+                }
+            }
+
+            egui_ctx.begin_frame(RawInput {
+                screen_rect: Some(Rect::from_min_size(pos2(0.0, 0.0), vec2(config.x() as f32, config.y() as f32))),
+                ..Default::default()
+            });
+            input.update(dt);
+            let running = game.update_game(dt, egui_ctx, input);
+            input.end_frame();
+            let _ = egui_ctx.end_frame();
+            if !running {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        let (tick, verified) = last;
+        match game.take_net_notice() {
+            // the peer finishing first is fine once every tick was played
+            Some(_) if tick >= ticks && game.get_net().is_none() && verified > 0 => {}
+            Some(notice) => {
+                eprintln!("online: {notice}");
+                return;
+            }
+            None => {}
+        }
+        println!("ticks {tick}, checksums verified {verified}, state {:016x}", game.get_current_scene().state_checksum());
+        self.save_screenshot(game);
+        game.open_scene(SceneId::Menu);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Result<Option<HeadlessOptions>, String> {
+        HeadlessOptions::from_args(std::iter::once("asteroids").chain(line.split_whitespace()).map(String::from))
+    }
+
+    #[test]
+    fn parses_headless_switches() {
+        let options = parse("--headless --scene menu --frames 12 --dt 0.5 --screenshot out.png --record clip.gif")
+            .unwrap()
+            .unwrap();
+
+        assert!(matches!(options.scene, SceneId::Menu));
+        assert_eq!(options.frames, 12);
+        assert_eq!(options.delta_time, 0.5);
+        assert_eq!(options.screenshot, Some(PathBuf::from("out.png")));
+        assert_eq!(options.record, Some(PathBuf::from("clip.gif")));
+
+        let options = parse("--headless --input Fire@0 --input-p3 Thrust@0-1").unwrap().unwrap();
+        assert_eq!(options.inputs, vec![(0, "Fire@0".to_string()), (2, "Thrust@0-1".to_string())]);
+
+        let options = parse("--headless --host 7777 --mode versus").unwrap().unwrap();
+        assert_eq!(options.host, Some(7777));
+        assert!(matches!(options.online_mode, GameMode::Versus { players: 2 }));
+        let options = parse("--headless --join 127.0.0.1:7777").unwrap().unwrap();
+        assert_eq!(options.join.as_deref(), Some("127.0.0.1:7777"));
+
+        assert!(parse("").unwrap().is_none());
+        assert!(parse("--frames 10").unwrap().is_none());
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert!(parse("--headless --fps 30").is_err());
+        assert!(parse("--headless --scene space").is_err());
+        assert!(parse("--headless --frames many").is_err());
+        assert!(parse("--headless --record").is_err());
+        assert!(parse("--headless --input-p9 Fire@0").is_err());
+        assert!(parse("--headless --host port").is_err());
+        assert!(parse("--headless --host 7777 --mode solo").is_err());
+    }
+}
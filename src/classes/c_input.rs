@@ -1,12 +1,16 @@
-﻿use std::collections::hash_map::Entry;
-use std::collections::HashMap;
-use vek::Lerp;
+﻿use std::collections::{HashMap, HashSet};
+use vek::{Lerp, Vec2};
+use winit::event::MouseButton;
 use winit::keyboard::KeyCode;
+use crate::classes::c_input_bindings::InputBindings;
+use crate::classes::c_keyboard_backend::{KeyboardBackend, KeyboardState};
+use crate::classes::e_action::{Action, Axis};
+use crate::classes::t_input_backend::InputBackend;
 
 
 #[derive(Clone, Debug)]
 pub struct InputAxis{
-    pub raw: i8,
+    pub raw: f32,
     pub lerp: f32,
 
     pub weight: f32
@@ -14,34 +18,71 @@ pub struct InputAxis{
 
 impl InputAxis{
     pub fn new(weight: f32) -> InputAxis{
-        InputAxis{ raw: 0, lerp: 0.0, weight }
+        InputAxis{ raw: 0.0, lerp: 0.0, weight }
     }
-    pub fn add(&mut self, v: i8) {
+    pub fn add(&mut self, v: f32) {
         self.raw += v;
     }
     pub fn reset(&mut self){
-        self.raw = 0;
+        self.raw = 0.0;
     }
 
     pub fn update(&mut self, dt: f32) {
-        self.lerp = f32::lerp(self.lerp, self.raw as f32, dt * self.weight);
+        self.raw = self.raw.clamp(-1.0, 1.0);
+        self.lerp = f32::lerp(self.lerp, self.raw, dt * self.weight);
     }
 }
 
 
-#[derive(Debug, Clone)]
-pub struct Input {
-    keys_states: HashMap<KeyCode, bool>,
+/// Held actions start repeating after this delay, for menu navigation.
+const REPEAT_DELAY: f32 = 0.35;
+const REPEAT_INTERVAL: f32 = 0.08;
+
+/// A press remembered for a short while so it can still be acted on when the game is not ready
+/// for it on the exact frame, e.g. fire during the shot cooldown.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BufferedPress {
+    /// Increases with every press, lets a consumer tell a new press from one it already used.
+    pub id: u64,
+    /// Seconds since the press.
+    pub age: f32,
+}
+
+/// Local players with their own action state; keyboard bindings exist for the first two,
+/// further players are driven by gamepads or virtual devices.
+pub const MAX_PLAYERS: usize = 4;
+
+/// Action state of one player, merged from the keyboard (through that player's bindings) and
+/// the backends assigned to the player.
+pub struct PlayerInput {
+    /// Actions held by any device, this frame and the previous one.
+    actions: HashSet<Action>,
+    prev_actions: HashSet<Action>,
+    /// Edges of the current frame, merged from frame comparison and device events.
+    pressed: HashSet<Action>,
+    released: HashSet<Action>,
+    presses: HashMap<Action, BufferedPress>,
+    next_press_id: u64,
+    held_time: HashMap<Action, f32>,
+    repeated: HashSet<Action>,
     horizontal: InputAxis,
     vertical: InputAxis,
-    fire: InputAxis
+    fire: InputAxis,
+    /// Rotate / thrust summed over the devices before smoothing, see `device_steering`.
+    steering: [f32; 2],
 }
 
-impl Default for Input {
-    fn default() -> Self {
+impl PlayerInput {
+    fn new() -> Self {
         Self{
-            keys_states: HashMap::new(),
-
+            actions: HashSet::new(),
+            prev_actions: HashSet::new(),
+            pressed: HashSet::new(),
+            released: HashSet::new(),
+            presses: HashMap::new(),
+            next_press_id: 1,
+            held_time: HashMap::new(),
+            repeated: HashSet::new(),
 
             horizontal: InputAxis::new(
                 10.0
@@ -51,21 +92,75 @@ impl Default for Input {
             ),
             fire: InputAxis::new(
                 100.0
-            )
+            ),
+            steering: [0.0; 2],
         }
     }
-}
 
-impl Input {
+    fn update(&mut self, dt: f32, devices: &[&dyn InputBackend]) {
+        self.prev_actions = std::mem::take(&mut self.actions);
+        self.actions = Action::ALL.into_iter()
+            .filter(|a| devices.iter().any(|d| d.is_action_down(*a)))
+            .collect();
+        self.update_edges(dt, devices);
 
-    pub fn on_key(&mut self, key: KeyCode, pressed: bool) {
+        self.horizontal.reset();
+        self.vertical.reset();
+        self.fire.reset();
+
+        if self.is_action_down(Action::Fire) {
+            self.fire.add(1.0);
+        }
+
+        // devices add up, the axis clamps the sum
+        self.steering = [0.0; 2];
+        for device in devices {
+            self.steering[0] += device_steering(*device, Axis::Rotate, Action::RotateLeft, Action::RotateRight);
+            self.steering[1] += device_steering(*device, Axis::Thrust, Action::Reverse, Action::Thrust);
+        }
+        self.horizontal.add(self.steering[0]);
+        self.vertical.add(self.steering[1]);
+
+        self.horizontal.update(dt);
+        self.vertical.update(dt);
+        self.fire.update(dt);
+    }
+
+    fn update_edges(&mut self, dt: f32, devices: &[&dyn InputBackend]) {
+        // device events catch taps that start and end between two updates
+        self.pressed = Action::ALL.into_iter()
+            .filter(|a| (self.actions.contains(a) && !self.prev_actions.contains(a)) || devices.iter().any(|d| d.is_action_pressed(*a)))
+            .collect();
+        self.released = Action::ALL.into_iter()
+            .filter(|a| (!self.actions.contains(a) && self.prev_actions.contains(a)) || devices.iter().any(|d| d.is_action_released(*a)))
+            .collect();
+
+        for press in self.presses.values_mut() {
+            press.age += dt;
+        }
+        for action in self.pressed.iter() {
+            self.presses.insert(*action, BufferedPress { id: self.next_press_id, age: 0.0 });
+            self.next_press_id += 1;
+        }
 
-        match self.keys_states.entry(key) {
-            Entry::Occupied(mut e) => {
-                *e.get_mut() = pressed;
-            },
-            Entry::Vacant(e) => {
-                e.insert(pressed);
+        self.repeated.clear();
+        for action in Action::ALL {
+            // a new press restarts the repeat timer, even when the key was re-pressed between updates
+            if self.pressed.contains(&action) {
+                self.repeated.insert(action);
+                self.held_time.insert(action, 0.0);
+                continue;
+            }
+            if !self.actions.contains(&action) {
+                self.held_time.remove(&action);
+                continue;
+            }
+
+            let held = self.held_time.entry(action).or_insert(0.0);
+            let before = *held;
+            *held += dt;
+            if repeat_count(*held) > repeat_count(before) {
+                self.repeated.insert(action);
             }
         }
     }
@@ -74,44 +169,492 @@ impl Input {
         self.horizontal.lerp
     }
 
-
     pub fn get_axis_ver(&self) -> f32{
         self.vertical.lerp
     }
 
     pub fn get_fire(&self) -> bool {
-        return self.fire.raw >= 1;
+        self.fire.raw >= 1.0
     }
 
-    pub fn update(&mut self, dt: f32) {
+    /// Rotate / thrust of all devices as of the last `update`, before clamping and smoothing.
+    pub fn get_steering(&self, axis: Axis) -> f32 {
+        match axis {
+            Axis::Rotate => self.steering[0],
+            Axis::Thrust => self.steering[1],
+        }
+    }
 
-        self.horizontal.reset();
-        self.vertical.reset();
-        self.fire.reset();
+    /// Held on any device as of the last `update`.
+    pub fn is_action_down(&self, action: Action) -> bool {
+        self.actions.contains(&action)
+    }
+
+    /// `true` on the frame the action went down on any device.
+    pub fn is_action_pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    /// `true` on the frame the action went up on any device.
+    pub fn is_action_released(&self, action: Action) -> bool {
+        self.released.contains(&action)
+    }
+
+    /// `true` on the press and then every `REPEAT_INTERVAL` once held for `REPEAT_DELAY`, like OS key repeat.
+    pub fn is_action_repeated(&self, action: Action) -> bool {
+        self.repeated.contains(&action)
+    }
+
+    /// The last press of `action` if it happened at most `window` seconds ago. Consumers remember
+    /// the `id` they acted on so one press triggers once even while it stays in the buffer.
+    pub fn get_buffered_press(&self, action: Action, window: f32) -> Option<BufferedPress> {
+        self.presses.get(&action).copied().filter(|p| p.age <= window)
+    }
+}
+
+pub struct Input {
+    keyboard: KeyboardState,
+    /// Extra devices (gamepads, virtual devices) with the index of the player they control.
+    backends: Vec<(usize, Box<dyn InputBackend>)>,
+    players: Vec<PlayerInput>,
+    /// Keyboard bindings per player.
+    bindings: Vec<InputBindings>,
+    mouse_states: HashMap<MouseButton, bool>,
+    /// Buttons that went down / up since the last `end_frame`.
+    mouse_pressed: HashSet<MouseButton>,
+    mouse_released: HashSet<MouseButton>,
+    /// Playfield coordinates, `None` while the cursor is outside the window or in the letterbox.
+    cursor: Option<Vec2<f32>>,
+    cursor_delta: Vec2<f32>,
+    /// Accumulated scroll in lines since the last `end_frame`, positive y scrolls up.
+    wheel: Vec2<f32>,
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Self{
+            keyboard: KeyboardState::default(),
+            backends: Vec::new(),
+            players: (0..MAX_PLAYERS).map(|_| PlayerInput::new()).collect(),
+            bindings: (0..MAX_PLAYERS).map(InputBindings::for_player).collect(),
+            mouse_states: HashMap::new(),
+            mouse_pressed: HashSet::new(),
+            mouse_released: HashSet::new(),
+            cursor: None,
+            cursor_delta: Vec2::zero(),
+            wheel: Vec2::zero(),
+        }
+    }
+}
 
+impl Input {
+
+    pub fn on_key(&mut self, key: KeyCode, pressed: bool) {
+        self.keyboard.on_key(key, pressed);
+    }
+
+    /// Adds a device for the first player.
+    pub fn add_backend(&mut self, backend: Box<dyn InputBackend>) {
+        self.add_player_backend(0, backend);
+    }
+
+    pub fn add_player_backend(&mut self, player: usize, backend: Box<dyn InputBackend>) {
+        self.backends.push((player.min(MAX_PLAYERS - 1), backend));
+    }
 
-        if (self.is_key_down(KeyCode::KeyA) || self.is_key_down(KeyCode::ArrowLeft)) {
-            self.horizontal.add(-1);
+    pub fn on_mouse_button(&mut self, button: MouseButton, pressed: bool) {
+        let was_down = self.is_mouse_down(button);
+        if pressed && !was_down {
+            self.mouse_pressed.insert(button);
         }
-        if (self.is_key_down(KeyCode::KeyD) || self.is_key_down(KeyCode::ArrowRight)) {
-            self.horizontal.add(1);
+        if !pressed && was_down {
+            self.mouse_released.insert(button);
         }
-        if (self.is_key_down(KeyCode::KeyW) || self.is_key_down(KeyCode::ArrowUp)) {
-            self.vertical.add(1);
+        self.mouse_states.insert(button, pressed);
+    }
+
+    pub fn on_cursor_moved(&mut self, cursor: Option<Vec2<f32>>) {
+        if let (Some(prev), Some(next)) = (self.cursor, cursor) {
+            self.cursor_delta += next - prev;
         }
-        if (self.is_key_down(KeyCode::Space)) {
-            self.fire.add(1);
+        self.cursor = cursor;
+    }
+
+    pub fn on_mouse_wheel(&mut self, lines: Vec2<f32>) {
+        self.wheel += lines;
+    }
+
+    pub fn get_cursor(&self) -> Option<Vec2<f32>> {
+        self.cursor
+    }
+
+    /// Cursor movement in playfield pixels during the current frame.
+    pub fn get_cursor_delta(&self) -> Vec2<f32> {
+        self.cursor_delta
+    }
+
+    pub fn get_wheel(&self) -> Vec2<f32> {
+        self.wheel
+    }
+
+    pub fn is_mouse_down(&self, button: MouseButton) -> bool {
+        self.mouse_states.get(&button).copied().unwrap_or(false)
+    }
+
+    /// `true` on the frame the button went down, even if it was released again before the frame ran.
+    pub fn is_mouse_pressed(&self, button: MouseButton) -> bool {
+        self.mouse_pressed.contains(&button)
+    }
+
+    pub fn is_mouse_released(&self, button: MouseButton) -> bool {
+        self.mouse_released.contains(&button)
+    }
+
+    /// Clears the per-frame edges and deltas, called once the frame has been simulated.
+    pub fn end_frame(&mut self) {
+        self.keyboard.end_frame();
+        for (_, backend) in self.backends.iter_mut() {
+            backend.end_frame();
         }
+        self.mouse_pressed.clear();
+        self.mouse_released.clear();
+        self.cursor_delta = Vec2::zero();
+        self.wheel = Vec2::zero();
+    }
 
-        self.horizontal.update(dt);
-        self.vertical.update(dt);
-        self.fire.update(dt);
+    pub fn update(&mut self, dt: f32) {
+        for (_, backend) in self.backends.iter_mut() {
+            backend.poll(dt);
+        }
+
+        for (index, player) in self.players.iter_mut().enumerate() {
+            let keyboard = KeyboardBackend { keys: &self.keyboard, bindings: &self.bindings[index] };
+            let devices: Vec<&dyn InputBackend> = std::iter::once(&keyboard as &dyn InputBackend)
+                .chain(self.backends.iter().filter(|(p, _)| *p == index).map(|(_, b)| b.as_ref()))
+                .collect();
+            player.update(dt, &devices);
+        }
+    }
+
+    /// Input of one local player, `0` is the first.
+    pub fn player(&self, index: usize) -> &PlayerInput {
+        &self.players[index.min(MAX_PLAYERS - 1)]
+    }
+
+    /// `true` when something can steer `player`: keyboard bindings or a connected device.
+    pub fn is_player_controllable(&self, player: usize) -> bool {
+        self.bindings.get(player).is_some_and(|b| !b.is_empty())
+            || self.backends.iter().any(|(p, b)| *p == player && b.is_connected())
+    }
+
+    pub fn set_player_bindings(&mut self, player: usize, bindings: InputBindings) {
+        if let Some(b) = self.bindings.get_mut(player) {
+            *b = bindings;
+        }
+    }
+
+    // Menus follow the first player.
+
+    pub fn is_action_pressed(&self, action: Action) -> bool {
+        self.player(0).is_action_pressed(action)
+    }
+
+    pub fn is_action_repeated(&self, action: Action) -> bool {
+        self.player(0).is_action_repeated(action)
+    }
+
+    /// Any key, mouse or device input this frame, for idle timers.
+    pub fn has_activity(&self) -> bool {
+        !self.keyboard.get_pressed_keys().is_empty()
+            || !self.mouse_pressed.is_empty()
+            || self.cursor_delta != Vec2::zero()
+            || self.wheel != Vec2::zero()
+            || self.players.iter().any(|p| !p.pressed.is_empty() || p.steering != [0.0; 2])
+    }
+
+    /// Keys that went down this frame, used to capture new bindings.
+    pub fn get_pressed_keys(&self) -> &[KeyCode] {
+        self.keyboard.get_pressed_keys()
     }
 
     pub fn is_key_down(&self, key: KeyCode) -> bool {
-        if let Some(v) = self.keys_states.get(&key) {
-            return v.clone();
+        self.keyboard.is_key_down(key)
+    }
+}
+
+/// A device steers with its analog value when it has one. Its digital actions along that axis
+/// (a stick past `press_threshold`) then only serve menus and edges, otherwise any deflection
+/// past the threshold would snap to full.
+fn device_steering(device: &dyn InputBackend, axis: Axis, negative: Action, positive: Action) -> f32 {
+    let analog = device.get_axis(axis);
+    if analog != 0.0 {
+        return analog;
+    }
+    device.is_action_down(positive) as i32 as f32 - device.is_action_down(negative) as i32 as f32
+}
+
+/// Repeats fired after holding for `held` seconds, not counting the initial press.
+fn repeat_count(held: f32) -> i32 {
+    if held < REPEAT_DELAY {
+        0
+    } else {
+        ((held - REPEAT_DELAY) / REPEAT_INTERVAL) as i32 + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classes::c_input_bindings::InputBindings;
+    use crate::classes::c_virtual_device::{VirtualDevice, VirtualEvent};
+
+    #[test]
+    fn mouse_edges_last_one_frame() {
+        let mut input = Input::default();
+
+        input.on_mouse_button(MouseButton::Left, true);
+        input.on_mouse_button(MouseButton::Left, true);
+        assert!(input.is_mouse_pressed(MouseButton::Left));
+        assert!(input.is_mouse_down(MouseButton::Left));
+        input.end_frame();
+        assert!(!input.is_mouse_pressed(MouseButton::Left));
+
+        // a click faster than a frame still reports both edges
+        input.on_mouse_button(MouseButton::Right, true);
+        input.on_mouse_button(MouseButton::Right, false);
+        assert!(input.is_mouse_pressed(MouseButton::Right));
+        assert!(input.is_mouse_released(MouseButton::Right));
+        assert!(!input.is_mouse_down(MouseButton::Right));
+    }
+
+    #[test]
+    fn actions_follow_bindings() {
+        let mut input = Input::default();
+        let mut bindings = InputBindings::default();
+        bindings.bind(Action::Fire, 0, KeyCode::KeyJ);
+        input.set_player_bindings(0, bindings);
+
+        input.on_key(KeyCode::Space, true);
+        input.update(1.0 / 60.0);
+        assert!(!input.player(0).get_fire());
+
+        input.on_key(KeyCode::KeyJ, true);
+        input.update(1.0 / 60.0);
+        assert!(input.player(0).get_fire());
+        assert!(input.is_action_pressed(Action::Fire));
+
+        input.end_frame();
+        input.on_key(KeyCode::KeyJ, true);
+        input.update(1.0 / 60.0);
+        assert!(!input.is_action_pressed(Action::Fire));
+        assert!(input.player(0).is_action_down(Action::Fire));
+    }
+
+    #[test]
+    fn analog_devices_are_not_snapped_by_their_own_actions() {
+        let stick = VirtualDevice::default();
+        let mut input = Input::default();
+        input.add_backend(Box::new(stick.clone()));
+
+        // a stick past the press threshold also holds the digital action
+        stick.schedule(0.0, VirtualEvent::SetAxis(Axis::Rotate, 0.6));
+        stick.schedule(0.0, VirtualEvent::Press(Action::RotateRight));
+        for _ in 0..120 {
+            input.update(1.0 / 60.0);
+            input.end_frame();
+        }
+        assert!(input.player(0).is_action_down(Action::RotateRight));
+        assert!((input.player(0).get_axis_hor() - 0.6).abs() < 0.01);
+
+        // a second device still adds its digital action
+        input.on_key(KeyCode::KeyA, true);
+        for _ in 0..120 {
+            input.update(1.0 / 60.0);
+            input.end_frame();
+        }
+        assert!((input.player(0).get_axis_hor() + 0.4).abs() < 0.01);
+    }
+
+    #[test]
+    fn virtual_device_feeds_actions_and_axes() {
+        let mut input = Input::default();
+        let pad = VirtualDevice::default();
+        input.add_backend(Box::new(pad.clone()));
+
+        pad.schedule(0.05, VirtualEvent::Press(Action::Hyperspace));
+        pad.schedule(0.1, VirtualEvent::Release(Action::Hyperspace));
+        pad.schedule(0.0, VirtualEvent::SetAxis(Axis::Rotate, 0.5));
+
+        let mut pressed_frames = 0;
+        for _ in 0..30 {
+            input.update(1.0 / 60.0);
+            if input.is_action_pressed(Action::Hyperspace) {
+                pressed_frames += 1;
+            }
+            input.end_frame();
+        }
+
+        assert_eq!(pressed_frames, 1);
+        assert!(!input.player(0).is_action_down(Action::Hyperspace));
+        assert!(input.player(0).get_axis_hor() > 0.4 && input.player(0).get_axis_hor() <= 0.5);
+        assert_eq!(input.player(0).get_axis_ver(), 0.0);
+    }
+
+    #[test]
+    fn scripted_device_taps_and_holds() {
+        let mut input = Input::default();
+        input.add_backend(Box::new(VirtualDevice::from_script("thrust@0.1-0.2, Fire@0.15, Rotate=-1@0").unwrap()));
+        assert!(VirtualDevice::from_script("Warp@1").is_err());
+
+        let mut thrust_frames = 0;
+        let mut fire_frames = 0;
+        for _ in 0..30 {
+            input.update(1.0 / 60.0);
+            thrust_frames += input.player(0).is_action_down(Action::Thrust) as u32;
+            fire_frames += input.is_action_pressed(Action::Fire) as u32;
+            input.end_frame();
+        }
+
+        assert!((5..=7).contains(&thrust_frames));
+        assert_eq!(fire_frames, 1);
+        assert!(input.player(0).get_axis_hor() < -0.9);
+    }
+
+    #[test]
+    fn sub_frame_tap_reports_both_edges() {
+        let mut input = Input::default();
+
+        input.on_key(KeyCode::Space, true);
+        input.on_key(KeyCode::Space, false);
+        input.update(1.0 / 60.0);
+        assert!(input.is_action_pressed(Action::Fire));
+        assert!(input.player(0).is_action_released(Action::Fire));
+        assert!(!input.player(0).is_action_down(Action::Fire));
+        input.end_frame();
+
+        input.update(1.0 / 60.0);
+        assert!(!input.is_action_pressed(Action::Fire));
+        assert!(!input.player(0).is_action_released(Action::Fire));
+    }
+
+    #[test]
+    fn held_action_repeats_after_delay() {
+        let mut input = Input::default();
+        let dt = 0.01;
+
+        input.on_key(KeyCode::ArrowUp, true);
+        let mut repeats: Vec<i32> = vec![];
+        for frame in 0..60 {
+            input.update(dt);
+            if input.is_action_repeated(Action::Thrust) {
+                repeats.push(frame);
+            }
+            input.end_frame();
+        }
+
+        // press at frame 0, first repeat after 0.35 s, then every 0.08 s
+        assert_eq!(repeats.len(), 4);
+        assert_eq!(repeats[0], 0);
+        assert!((34..=36).contains(&repeats[1]));
+        assert!((repeats[2] - repeats[1] - 8).abs() <= 1);
+    }
+
+    #[test]
+    fn buffered_press_expires_and_renews() {
+        let mut input = Input::default();
+
+        input.on_key(KeyCode::KeyH, true);
+        input.update(0.05);
+        let first = input.player(0).get_buffered_press(Action::Hyperspace, 0.2).unwrap();
+        input.end_frame();
+
+        input.on_key(KeyCode::KeyH, false);
+        for _ in 0..3 {
+            input.update(0.05);
+            input.end_frame();
         }
-        false
+        assert_eq!(input.player(0).get_buffered_press(Action::Hyperspace, 0.2).map(|p| p.id), Some(first.id));
+
+        input.update(0.1);
+        assert!(input.player(0).get_buffered_press(Action::Hyperspace, 0.2).is_none());
+        input.end_frame();
+
+        input.on_key(KeyCode::KeyH, true);
+        input.update(0.05);
+        assert!(input.player(0).get_buffered_press(Action::Hyperspace, 0.2).unwrap().id > first.id);
+    }
+
+    #[test]
+    fn cursor_delta_and_wheel_accumulate() {
+        let mut input = Input::default();
+
+        input.on_cursor_moved(Some(Vec2::new(10.0, 10.0)));
+        input.on_cursor_moved(Some(Vec2::new(15.0, 12.0)));
+        input.on_cursor_moved(Some(Vec2::new(20.0, 8.0)));
+        input.on_mouse_wheel(Vec2::new(0.0, 1.0));
+        input.on_mouse_wheel(Vec2::new(0.0, 2.0));
+        assert_eq!(input.get_cursor_delta(), Vec2::new(10.0, -2.0));
+        assert_eq!(input.get_wheel(), Vec2::new(0.0, 3.0));
+
+        input.end_frame();
+        assert_eq!(input.get_cursor_delta(), Vec2::zero());
+        assert_eq!(input.get_wheel(), Vec2::zero());
+    }
+
+    #[test]
+    fn players_past_the_keyboard_need_a_connected_device() {
+        let mut input = Input::default();
+        assert!(input.is_player_controllable(0));
+        assert!(input.is_player_controllable(1));
+        assert!(!input.is_player_controllable(2));
+
+        input.add_player_backend(2, Box::new(VirtualDevice::default()));
+        assert!(input.is_player_controllable(2));
+        assert!(!input.is_player_controllable(3));
+    }
+
+    #[test]
+    fn activity_covers_keys_mouse_and_devices() {
+        let device = VirtualDevice::default();
+        let mut input = Input::default();
+        input.add_player_backend(1, Box::new(device.clone()));
+
+        input.update(0.016);
+        assert!(!input.has_activity());
+
+        input.on_key(KeyCode::KeyQ, true);
+        input.update(0.016);
+        assert!(input.has_activity());
+        input.end_frame();
+
+        input.update(0.016);
+        assert!(!input.has_activity());
+        input.on_cursor_moved(Some(Vec2::new(1.0, 1.0)));
+        input.on_cursor_moved(Some(Vec2::new(4.0, 1.0)));
+        assert!(input.has_activity());
+        input.end_frame();
+
+        device.schedule(0.0, VirtualEvent::SetAxis(Axis::Rotate, 0.5));
+        input.update(0.016);
+        assert!(input.has_activity());
+    }
+
+    #[test]
+    fn players_read_their_own_keys_and_devices() {
+        let mut input = Input::default();
+        let pad = VirtualDevice::default();
+        input.add_player_backend(1, Box::new(pad.clone()));
+        pad.schedule(0.0, VirtualEvent::Press(Action::Hyperspace));
+
+        input.on_key(KeyCode::Space, true);
+        input.on_key(KeyCode::KeyJ, true);
+        input.update(1.0 / 60.0);
+
+        assert!(input.player(0).get_fire());
+        assert!(!input.player(1).get_fire());
+        assert!(input.player(1).is_action_down(Action::RotateLeft));
+        assert!(!input.player(0).is_action_down(Action::RotateLeft));
+        assert!(input.player(1).is_action_pressed(Action::Hyperspace));
+        assert!(!input.player(0).is_action_down(Action::Hyperspace));
     }
 }
@@ -0,0 +1,165 @@
+﻿use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use winit::keyboard::KeyCode;
+use crate::classes::e_action::Action;
+
+/// Keys bound to each `Action`. Saved in the config as `{"Thrust": ["KeyW", "ArrowUp"], ...}`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(transparent)]
+pub struct InputBindings {
+    keys: BTreeMap<Action, Vec<KeyCode>>,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        Self::for_player(0)
+    }
+}
+
+fn default_keys(player: usize, action: Action) -> Vec<KeyCode> {
+    match player {
+        0 => default_keys_p1(action),
+        1 => default_keys_p2(action),
+        // further players only play on gamepads
+        _ => Vec::new(),
+    }
+}
+
+/// The second player sits on the right half of the keyboard and does not drive the menus.
+fn default_keys_p2(action: Action) -> Vec<KeyCode> {
+    match action {
+        Action::RotateLeft => vec![KeyCode::KeyJ],
+        Action::RotateRight => vec![KeyCode::KeyL],
+        Action::Thrust => vec![KeyCode::KeyI],
+        Action::Reverse => vec![KeyCode::KeyK],
+        Action::Fire => vec![KeyCode::KeyO, KeyCode::ControlRight],
+        Action::Hyperspace => vec![KeyCode::KeyU],
+        _ => Vec::new(),
+    }
+}
+
+fn default_keys_p1(action: Action) -> Vec<KeyCode> {
+    match action {
+        Action::RotateLeft => vec![KeyCode::KeyA, KeyCode::ArrowLeft],
+        Action::RotateRight => vec![KeyCode::KeyD, KeyCode::ArrowRight],
+        Action::Thrust => vec![KeyCode::KeyW, KeyCode::ArrowUp],
+        Action::Reverse => vec![KeyCode::KeyS, KeyCode::ArrowDown],
+        Action::Fire => vec![KeyCode::Space],
+        Action::Hyperspace => vec![KeyCode::ShiftLeft, KeyCode::KeyH],
+        Action::Pause => vec![KeyCode::Escape, KeyCode::KeyP],
+        Action::MenuUp => vec![KeyCode::ArrowUp],
+        Action::MenuDown => vec![KeyCode::ArrowDown, KeyCode::Tab],
+        Action::MenuLeft => vec![KeyCode::ArrowLeft],
+        Action::MenuRight => vec![KeyCode::ArrowRight],
+        Action::MenuConfirm => vec![KeyCode::Enter, KeyCode::NumpadEnter],
+        Action::MenuBack => vec![KeyCode::Escape],
+    }
+}
+
+impl InputBindings {
+    /// Default bindings of local player `player`, `0` is the first.
+    pub fn for_player(player: usize) -> Self {
+        let keys = Action::ALL.iter()
+            .map(|action| (*action, default_keys(player, *action)))
+            .collect();
+        Self { keys }
+    }
+
+    /// `true` when no action has a key, like the defaults of players past the keyboard ones.
+    pub fn is_empty(&self) -> bool {
+        self.keys.values().all(|keys| keys.is_empty())
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        self.keys.get(&action).map(|k| k.as_slice()).unwrap_or(&[])
+    }
+
+    /// Puts `key` into binding `slot` of `action` (appending when the slot does not exist yet)
+    /// and removes it from the other actions of the same group, so one key never drives two
+    /// gameplay (or two menu) actions.
+    pub fn bind(&mut self, action: Action, slot: usize, key: KeyCode) {
+        self.remove_key(key, action.is_menu());
+
+        let keys = self.keys.entry(action).or_default();
+        if slot < keys.len() {
+            keys[slot] = key;
+        } else {
+            keys.push(key);
+        }
+    }
+
+    /// Drops `key` from every gameplay action (`menu == false`) or every menu action.
+    pub fn remove_key(&mut self, key: KeyCode, menu: bool) {
+        for (action, keys) in self.keys.iter_mut() {
+            if action.is_menu() == menu {
+                keys.retain(|k| *k != key);
+            }
+        }
+    }
+
+    pub fn unbind(&mut self, action: Action, slot: usize) {
+        if let Some(keys) = self.keys.get_mut(&action) && slot < keys.len() {
+            keys.remove(slot);
+        }
+    }
+
+    /// Adds `player`'s default keys for actions missing from an older config.
+    pub fn fill_missing(&mut self, player: usize) {
+        for action in Action::ALL {
+            self.keys.entry(action).or_insert_with(|| default_keys(player, action));
+        }
+    }
+}
+
+/// Short display name, `KeyW` -> `W`, `Digit1` -> `1`.
+pub fn key_name(key: KeyCode) -> String {
+    let name = format!("{:?}", key);
+    name.strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Digit"))
+        .unwrap_or(&name)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bind_moves_key_between_actions() {
+        let mut bindings = InputBindings::default();
+        bindings.bind(Action::Fire, 1, KeyCode::KeyW);
+
+        assert_eq!(bindings.keys(Action::Fire), &[KeyCode::Space, KeyCode::KeyW]);
+        assert_eq!(bindings.keys(Action::Thrust), &[KeyCode::ArrowUp]);
+
+        bindings.bind(Action::Fire, 0, KeyCode::Enter);
+        assert_eq!(bindings.keys(Action::MenuConfirm), &[KeyCode::Enter, KeyCode::NumpadEnter]);
+    }
+
+    #[test]
+    fn bindings_round_trip_through_json() {
+        let mut bindings = InputBindings::default();
+        bindings.unbind(Action::Pause, 0);
+
+        let json = serde_json::to_string(&bindings).unwrap();
+        assert!(json.contains("\"Fire\":[\"Space\"]"));
+
+        let mut loaded: InputBindings = serde_json::from_str(r#"{"Fire":["KeyJ"]}"#).unwrap();
+        loaded.fill_missing(0);
+        assert_eq!(loaded.keys(Action::Fire), &[KeyCode::KeyJ]);
+        assert_eq!(loaded.keys(Action::Thrust), &[KeyCode::KeyW, KeyCode::ArrowUp]);
+        assert_eq!(serde_json::from_str::<InputBindings>(&json).unwrap(), bindings);
+    }
+
+    #[test]
+    fn player_defaults_do_not_share_keys() {
+        let p1 = InputBindings::for_player(0);
+        let p2 = InputBindings::for_player(1);
+        for a in Action::ALL {
+            for b in Action::ALL {
+                assert!(p2.keys(b).iter().all(|k| !p1.keys(a).contains(k)), "{:?} / {:?}", a, b);
+            }
+        }
+        assert!(InputBindings::for_player(2).keys(Action::Fire).is_empty());
+    }
+}
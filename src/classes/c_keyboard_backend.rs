@@ -0,0 +1,59 @@
+﻿use std::collections::HashMap;
+use winit::keyboard::KeyCode;
+use crate::classes::c_input_bindings::InputBindings;
+use crate::classes::e_action::Action;
+use crate::classes::t_input_backend::InputBackend;
+
+/// Raw keyboard state fed by window events, shared by every player's bindings.
+#[derive(Default, Debug)]
+pub struct KeyboardState {
+    keys_states: HashMap<KeyCode, bool>,
+    /// Keys that went down / up since the last `end_frame`, in event order.
+    keys_pressed: Vec<KeyCode>,
+    keys_released: Vec<KeyCode>,
+}
+
+impl KeyboardState {
+    pub fn on_key(&mut self, key: KeyCode, pressed: bool) {
+        if pressed && !self.is_key_down(key) {
+            self.keys_pressed.push(key);
+        }
+        if !pressed && self.is_key_down(key) {
+            self.keys_released.push(key);
+        }
+        self.keys_states.insert(key, pressed);
+    }
+
+    pub fn is_key_down(&self, key: KeyCode) -> bool {
+        self.keys_states.get(&key).copied().unwrap_or(false)
+    }
+
+    pub fn get_pressed_keys(&self) -> &[KeyCode] {
+        &self.keys_pressed
+    }
+
+    pub fn end_frame(&mut self) {
+        self.keys_pressed.clear();
+        self.keys_released.clear();
+    }
+}
+
+/// The keyboard seen through one player's bindings.
+pub struct KeyboardBackend<'a> {
+    pub keys: &'a KeyboardState,
+    pub bindings: &'a InputBindings,
+}
+
+impl InputBackend for KeyboardBackend<'_> {
+    fn is_action_down(&self, action: Action) -> bool {
+        self.bindings.keys(action).iter().any(|k| self.keys.is_key_down(*k))
+    }
+
+    fn is_action_pressed(&self, action: Action) -> bool {
+        self.bindings.keys(action).iter().any(|k| self.keys.keys_pressed.contains(k))
+    }
+
+    fn is_action_released(&self, action: Action) -> bool {
+        self.bindings.keys(action).iter().any(|k| self.keys.keys_released.contains(k))
+    }
+}
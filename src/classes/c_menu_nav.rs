@@ -0,0 +1,168 @@
+﻿use egui::{Context, Response, Ui};
+use crate::classes::c_input::Input;
+use crate::classes::e_action::Action;
+use crate::render_lib::f_drawers::{ui_button_response, ui_focus_highlight};
+
+/// Keyboard / gamepad focus for egui menus. The scene feeds menu actions in `update`, then every
+/// ui pass calls `begin`, registers its focusable widgets in order and calls `end`.
+/// Up / left move to the previous widget, down / right to the next, wrapping around.
+#[derive(Default)]
+pub struct MenuNav {
+    focus: usize,
+    /// Focusable widgets seen during the last ui pass.
+    count: usize,
+    vertical: i32,
+    horizontal: i32,
+    confirm: bool,
+    back: bool,
+    /// Focus changed by keys this pass, text fields grab egui focus and scroll areas follow.
+    moved: bool,
+}
+
+impl MenuNav {
+    pub fn update(&mut self, input: &Input) {
+        self.vertical += input.is_action_repeated(Action::MenuDown) as i32 - input.is_action_repeated(Action::MenuUp) as i32;
+        self.horizontal += input.is_action_repeated(Action::MenuRight) as i32 - input.is_action_repeated(Action::MenuLeft) as i32;
+        self.confirm |= input.is_action_pressed(Action::MenuConfirm);
+        self.back |= input.is_action_pressed(Action::MenuBack);
+    }
+
+    /// Focuses the first widget, e.g. after switching tabs.
+    pub fn reset(&mut self) {
+        *self = MenuNav { moved: true, ..Default::default() };
+    }
+
+    pub fn begin(&mut self, ctx: &Context) {
+        // left / right belong to the text cursor while a text field is being edited
+        let horizontal = if ctx.wants_keyboard_input() { 0 } else { self.horizontal };
+        let step = self.vertical + horizontal;
+        if step != 0 && self.count > 0 {
+            self.focus = (self.focus as i32 + step).rem_euclid(self.count as i32) as usize;
+            self.moved = true;
+        }
+
+        self.vertical = 0;
+        self.horizontal = 0;
+        self.count = 0;
+    }
+
+    pub fn end(&mut self) {
+        if self.count > 0 {
+            self.focus = self.focus.min(self.count - 1);
+        }
+        self.confirm = false;
+        self.back = false;
+        self.moved = false;
+    }
+
+    /// `true` once when back (Escape / B) was pressed.
+    pub fn take_back(&mut self) -> bool {
+        std::mem::take(&mut self.back)
+    }
+
+    /// A standard menu button, `true` when clicked or confirmed while focused.
+    pub fn button(&mut self, ui: &mut Ui, name: &str) -> bool {
+        self.widget(ui, |ui| ui_button_response(ui, name))
+    }
+
+    /// Any clickable widget, `true` when clicked or confirmed while focused and enabled.
+    pub fn widget(&mut self, ui: &mut Ui, add: impl FnOnce(&mut Ui) -> Response) -> bool {
+        let (response, focused) = self.register(ui, add);
+        response.clicked() || (focused && self.confirm && response.enabled())
+    }
+
+    /// A text field gets egui keyboard focus while it has menu focus; confirm moves on to the next widget.
+    pub fn text_edit(&mut self, ui: &mut Ui, add: impl FnOnce(&mut Ui) -> Response) -> Response {
+        let (response, focused) = self.register(ui, add);
+
+        if response.clicked() || response.gained_focus() {
+            self.focus = self.count - 1;
+        } else if focused && self.confirm {
+            self.focus += 1;
+            self.confirm = false;
+        } else if focused && self.moved && !response.has_focus() {
+            response.request_focus();
+        } else if !focused && response.has_focus() {
+            response.surrender_focus();
+        }
+
+        response
+    }
+
+    fn register(&mut self, ui: &mut Ui, add: impl FnOnce(&mut Ui) -> Response) -> (Response, bool) {
+        let index = self.count;
+        self.count += 1;
+
+        let response = add(ui);
+        let pointer_moved = ui.input(|i| i.pointer.delta() != egui::Vec2::ZERO);
+        if response.hovered() && pointer_moved {
+            self.focus = index;
+        }
+
+        let focused = self.focus == index;
+        if focused {
+            ui_focus_highlight(ui, response.rect);
+            if self.moved {
+                response.scroll_to_me(None);
+            }
+        }
+        (response, focused)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{pos2, vec2, RawInput, Rect};
+    use winit::keyboard::KeyCode;
+    use super::*;
+
+    /// One frame: tap `key`, feed the menu actions and draw three buttons; returns the activated ones.
+    fn frame(ctx: &Context, nav: &mut MenuNav, input: &mut Input, key: Option<KeyCode>) -> Vec<usize> {
+        if let Some(key) = key {
+            input.on_key(key, true);
+        }
+        input.update(1.0 / 60.0);
+        nav.update(input);
+        input.end_frame();
+        if let Some(key) = key {
+            input.on_key(key, false);
+        }
+
+        let mut activated = vec![];
+        ctx.begin_frame(RawInput {
+            screen_rect: Some(Rect::from_min_size(pos2(0.0, 0.0), vec2(800.0, 600.0))),
+            ..Default::default()
+        });
+        egui::CentralPanel::default().show(ctx, |ui| {
+            nav.begin(ui.ctx());
+            for i in 0..3 {
+                if nav.button(ui, &format!("button {i}")) {
+                    activated.push(i);
+                }
+            }
+            nav.end();
+        });
+        let _ = ctx.end_frame();
+        activated
+    }
+
+    #[test]
+    fn keys_move_focus_and_activate() {
+        let ctx = Context::default();
+        let mut nav = MenuNav::default();
+        let mut input = Input::default();
+
+        assert!(frame(&ctx, &mut nav, &mut input, None).is_empty());
+        frame(&ctx, &mut nav, &mut input, Some(KeyCode::ArrowDown));
+        frame(&ctx, &mut nav, &mut input, None);
+        assert_eq!(frame(&ctx, &mut nav, &mut input, Some(KeyCode::Enter)), vec![1]);
+
+        // wraps around from the first button to the last
+        frame(&ctx, &mut nav, &mut input, Some(KeyCode::ArrowUp));
+        frame(&ctx, &mut nav, &mut input, Some(KeyCode::ArrowUp));
+        assert_eq!(frame(&ctx, &mut nav, &mut input, Some(KeyCode::Enter)), vec![2]);
+
+        frame(&ctx, &mut nav, &mut input, Some(KeyCode::Escape));
+        assert!(!nav.take_back(), "back is only reported during the ui pass");
+    }
+}
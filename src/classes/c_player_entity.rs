@@ -1,94 +1,131 @@
 ﻿use std::rc::Rc;
+use rand::Rng;
 use vek::Vec2;
 use crate::assetsdb_lib::c_assets_db::AssetsDB;
 use crate::classes::bullet_entity::BulletEntity;
-use crate::classes::c_input::Input;
+use crate::classes::c_input::{Input, MAX_PLAYERS};
+use crate::classes::e_action::Action;
 use crate::classes::t_entity::Entity;
 use crate::collisions_lib::e_col_layers::ColLayer;
-use crate::collisions_lib::t_collision::Collide;
+use crate::collisions_lib::t_collision::{Collide, Contact};
 use crate::config_lib::c_config::Config;
-use crate::mesh_lib::c_mesh::{Mesh, MeshLine};
+use crate::mesh_lib::c_mesh::Mesh;
+use crate::render_lib::c_particle_effect::{ParticleEffect, ParticleEmitter};
+use crate::render_lib::f_drawers::draw_mesh;
 use crate::render_lib::t_drawable::Drawable;
 use crate::render_lib::t_screen_data::Screen;
 use crate::scenes_lib::e_scene_event::SceneEvent;
 use crate::scenes_lib::e_scene_event::SceneEvent::SpawnEntity;
 use crate::transform_lib::c_transform::Transform;
+use crate::random_lib::c_sim_rng::sim_rng;
+
+/// Presses this recent still trigger a shot or jump once the cooldown allows it.
+const INPUT_BUFFER: f32 = 0.15;
+
+/// Seconds a destroyed ship stays off the field before it respawns, see `set_respawn_delay`.
+const RESPAWN_DELAY: f32 = 1.5;
+
+/// Ship colour per local player.
+pub const PLAYER_COLORS: [u32; MAX_PLAYERS] = [0xFFFFFF, 0x66CCFF, 0xFFB040, 0x90FF70];
 
 pub struct PlayerEntity {
     id: u32,
+    /// Local player index, selects the input and the colour.
+    index: usize,
+    spawn_point: Vec2<f32>,
     transform: Transform,
     mesh: Rc<Mesh>,
+    thrust_emitter: ParticleEmitter,
 
     max_speed: f32,
     acceleration_speed: f32,
     decceleration_speed: f32,
     shoot_cooldown: f32,
+    hyperspace_cooldown: f32,
 
     aceel: f32,
 
     shoot_time: f32,
+    hyperspace_time: f32,
     god_mode_time: f32,
 
     global_time: f32,
 
     is_hitted: bool,
+    /// Player whose bullet destroyed the ship, for versus scoring.
+    hit_by: Option<usize>,
     is_god_mode: bool,
+    respawn_delay: f32,
+    /// Time left until the destroyed ship comes back, `None` while it flies.
+    respawn_time: Option<f32>,
+
+    /// Ids of the last buffered presses acted on.
+    fire_press: u64,
+    hyperspace_press: u64,
 
 }
 
 
 impl PlayerEntity {
-    pub fn new(transform: Transform, mesh: Rc<Mesh>) -> PlayerEntity {
+    /// The ship respawns where `transform` places it.
+    pub fn new(index: usize, transform: Transform, mesh: Rc<Mesh>, thrust: Rc<ParticleEffect>) -> PlayerEntity {
         PlayerEntity {
             id: 0,
+            index,
+            spawn_point: *transform.get_position(),
             transform: transform,
             mesh: mesh,
+            thrust_emitter: ParticleEmitter::new(thrust),
             max_speed: 650.0,
             acceleration_speed: 250.0,
             decceleration_speed: 1.0,
             aceel: 0.0,
             shoot_cooldown: 0.25,
             shoot_time: 0.0,
+            hyperspace_cooldown: 2.0,
+            hyperspace_time: 2.0,
             global_time: 0.0,
             god_mode_time: 0.0,
             is_god_mode: false,
             is_hitted: false,
+            hit_by: None,
+            respawn_delay: RESPAWN_DELAY,
+            respawn_time: None,
+            fire_press: 0,
+            hyperspace_press: 0,
         }
     }
 
-    fn create_thruster_mesh(dist: f32, wobble: f32) -> Mesh {
-        let procedural_truster_mesh = Mesh::new(
-            "thruster".to_string(),
-            vec![
-                MeshLine::new(Vec2::new(-80.0, 30.0), Vec2::new(dist-80.0, wobble)),
-                MeshLine::new(Vec2::new(-80.0, -30.0), Vec2::new(dist-80.0, wobble)),
-            ],
-            false
-        );
-        procedural_truster_mesh
+    /// `0.0` puts a destroyed ship straight back on its spawn point, like the solo game always did.
+    pub fn set_respawn_delay(&mut self, delay: f32) {
+        self.respawn_delay = delay;
     }
 }
 
 impl Drawable for PlayerEntity {
-    fn draw(&mut self, screen: &mut Screen) {
-
-
-        if (self.is_god_mode){
-            let sin = (self.global_time * 20.0).sin();
-            if (sin > 0.5){
-                return;
-            }
-        }
-
-        self.draw_mesh(screen, &self.transform, &self.mesh);
+    fn enabled_renderer(&self) -> bool {
+        // blink while invulnerable
+        self.respawn_time.is_none() && (!self.is_god_mode || (self.global_time * 20.0).sin() <= 0.5)
+    }
 
-        let truster = Self::create_thruster_mesh(-self.transform.get_velocity().magnitude() / 5.0, (self.global_time * 20.0).sin() * 5.0);
+    fn get_z_order(&self) -> i32 {
+        30
+    }
 
-        self.draw_mesh(screen, &self.transform, &truster);
+    fn draw(&mut self, screen: &mut Screen) {
+        draw_mesh(screen, &self.transform, &self.mesh, PLAYER_COLORS[self.index]);
     }
 }
 
 impl Collide for PlayerEntity {
+    fn can_collide(&self) -> bool {
+        self.respawn_time.is_none()
+    }
+
+    fn get_owner(&self) -> Option<usize> {
+        Some(self.index)
+    }
+
     fn get_collision_layer(&self) -> ColLayer {
         return ColLayer::Player;
     }
@@ -97,10 +134,19 @@ impl Collide for PlayerEntity {
         Some((self.mesh.clone(), &self.transform))
     }
 
-    fn on_collision(&mut self, layer: ColLayer) {
-        if (self.is_god_mode) {return};
-        if (layer == ColLayer::BulletEnemy || layer == ColLayer::Asteroid || layer == ColLayer::Enemy){
-            self.is_hitted = true;
+    fn on_contact(&mut self, contact: &Contact) {
+        if self.is_god_mode || self.is_hitted {return};
+        match contact.layer {
+            ColLayer::BulletEnemy | ColLayer::Asteroid | ColLayer::Enemy => {
+                self.is_hitted = true;
+                self.hit_by = None;
+            }
+            // only reaches the ship when the scene's collision rules allow friendly fire
+            ColLayer::BulletPlayer => {
+                self.is_hitted = true;
+                self.hit_by = contact.owner;
+            }
+            ColLayer::Player => {}
         }
     }
 }
@@ -115,23 +161,36 @@ impl Entity for PlayerEntity {
     }
     fn get_position(&self) ->  &Vec2<f32> { return self.transform.get_position() }
 
+    fn get_velocity(&self) -> Vec2<f32> { *self.transform.get_velocity() }
+
     fn update(&mut self, delta_time: f32, input: &Input, config: &Config, assets_db: &AssetsDB) -> Vec<SceneEvent> {
 
         let mut events = vec![];
+        let input = input.player(self.index);
+
+        self.global_time += delta_time;
 
         if (self.is_hitted){
             events.push(SceneEvent::SpawnDebris(*self.transform.get_position()));
             self.is_hitted = false;
 
             self.transform.set_velocity(Vec2::zero());
-            self.transform.update_position_warp(Vec2::new(
-                (config.x()/2) as f32,
-                (config.y()/2) as f32
-            ));
+            self.aceel = 0.0;
+            self.respawn_time = Some(self.respawn_delay);
+
+            events.push(SceneEvent::PlayerDeath { player: self.index, by: self.hit_by.take() });
+        }
+
+        if let Some(time) = self.respawn_time {
+            if time > 0.0 {
+                self.respawn_time = Some(time - delta_time);
+                return events;
+            }
+
+            self.respawn_time = None;
+            self.transform.update_position_warp(self.spawn_point);
             self.god_mode_time = 0.0;
             self.is_god_mode = true;
-
-            events.push(SceneEvent::PlayerDeath);
         }
 
         if (self.is_god_mode){
@@ -144,17 +203,22 @@ impl Entity for PlayerEntity {
 
         self.transform.add_rotation(delta_time * 5.0 * input.get_axis_hor());
 
-        self.global_time += delta_time;
-
-        if input.get_axis_ver() > 0.5 {
+        let thrust = input.get_axis_ver();
+        if thrust.abs() > 0.5 {
             self.aceel = (self.aceel + (5.0 * delta_time)).min(1.0);
 
             let mut accel_val = self.aceel * self.acceleration_speed; // units/sec^2
-            let forward = self
+            let mut forward = self
                 .transform
                 .transform_dir_to_world(Vec2::new(1.0, 0.0))
                 .normalized();
 
+            // reverse thrusters are weaker than the main engine
+            if thrust < 0.0 {
+                forward = -forward;
+                accel_val *= 0.5;
+            }
+
             let mut v = self.transform.get_velocity().clone();
 
 
@@ -188,14 +252,37 @@ impl Entity for PlayerEntity {
 
         self.transform.update_position_by_vel(delta_time);
 
+        let count = self.thrust_emitter.update(delta_time, input.get_axis_ver() > 0.5);
+        if count > 0 {
+            events.push(SceneEvent::EmitParticles {
+                effect: self.thrust_emitter.get_effect().clone(),
+                count,
+                pos: self.transform.transform_point_to_world(Vec2::new(-80.0, 0.0)),
+                dir: self.transform.transform_dir_to_world(Vec2::new(-1.0, 0.0)),
+                velocity: *self.transform.get_velocity(),
+            });
+        }
+
+
+        self.hyperspace_time += delta_time;
+        if self.hyperspace_time >= self.hyperspace_cooldown
+            && let Some(press) = input.get_buffered_press(Action::Hyperspace, INPUT_BUFFER)
+            && press.id != self.hyperspace_press {
+            self.hyperspace(&mut events, config);
+            self.hyperspace_time = 0.0;
+            self.hyperspace_press = press.id;
+        }
 
         self.shoot_time += delta_time;
 
-        if (input.get_fire()) {
-            if (self.shoot_time >= self.shoot_cooldown) {
-                self.spawn_bullet(&mut events, assets_db, config);
+        // holding fire shoots on cooldown, a tap during the cooldown is kept for INPUT_BUFFER
+        let tap = input.get_buffered_press(Action::Fire, INPUT_BUFFER).filter(|p| p.id != self.fire_press);
+        if (input.get_fire() || tap.is_some()) && self.shoot_time >= self.shoot_cooldown {
+            self.spawn_bullet(&mut events, assets_db, config);
 
-                self.shoot_time = 0.0
+            self.shoot_time = 0.0;
+            if let Some(tap) = tap {
+                self.fire_press = tap.id;
             }
         }
 
@@ -205,6 +292,29 @@ impl Entity for PlayerEntity {
 }
 
 impl PlayerEntity {
+    /// Jumps to a random spot on the playfield and kills the ship's momentum.
+    fn hyperspace(&mut self, events: &mut Vec<SceneEvent>, config: &Config) {
+        let mut rng = sim_rng();
+        let target = Vec2::new(
+            rng.random_range(0.0..config.x() as f32),
+            rng.random_range(0.0..config.y() as f32),
+        );
+
+        for pos in [*self.transform.get_position(), target] {
+            events.push(SceneEvent::EmitParticles {
+                effect: self.thrust_emitter.get_effect().clone(),
+                count: 24,
+                pos,
+                dir: Vec2::unit_x(),
+                velocity: Vec2::zero(),
+            });
+        }
+
+        self.transform.set_velocity(Vec2::zero());
+        self.transform.update_position_warp(target);
+        self.aceel = 0.0;
+    }
+
     fn spawn_bullet(&mut self, events: &mut Vec<SceneEvent>, assets_db: &AssetsDB, config: &Config) {
         let mesh = assets_db.get_mesh_by_name("bullet");
         let spawn_point = self.transform.transform_point_to_world(Vec2::new(50.0, 0.0));
@@ -218,7 +328,8 @@ impl PlayerEntity {
             ),
             mesh.unwrap_or_default(),
             self.transform.get_velocity().magnitude(),
-            ColLayer::BulletPlayer
+            ColLayer::BulletPlayer,
+            Some(self.index)
         );
 
 
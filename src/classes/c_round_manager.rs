@@ -0,0 +1,113 @@
+﻿/// Kills needed to win a versus round.
+pub const DEFAULT_KILL_LIMIT: u32 = 5;
+
+/// Score keeping of a versus match: kills and deaths of the running round and rounds won.
+pub struct RoundManager {
+    kill_limit: u32,
+    round: u32,
+    kills: Vec<i32>,
+    deaths: Vec<u32>,
+    wins: Vec<u32>,
+    winner: Option<usize>,
+}
+
+impl RoundManager {
+    pub fn new(players: usize, kill_limit: u32) -> Self {
+        Self {
+            kill_limit,
+            round: 1,
+            kills: vec![0; players],
+            deaths: vec![0; players],
+            wins: vec![0; players],
+            winner: None,
+        }
+    }
+
+    /// Records a lost ship. A kill by another player scores for them; crashing into a hazard
+    /// costs the victim a kill. Bullets never hit their owner, so `killer` is never the victim.
+    /// Returns the round winner once the limit is hit.
+    pub fn on_death(&mut self, victim: usize, killer: Option<usize>) -> Option<usize> {
+        if self.winner.is_some() {
+            return self.winner;
+        }
+
+        self.deaths[victim] += 1;
+        match killer {
+            Some(killer) => {
+                self.kills[killer] += 1;
+                if self.kills[killer] >= self.kill_limit as i32 {
+                    self.winner = Some(killer);
+                    self.wins[killer] += 1;
+                }
+            }
+            None => self.kills[victim] -= 1,
+        }
+        self.winner
+    }
+
+    /// Clears kills and deaths, rounds won carry over.
+    pub fn next_round(&mut self) {
+        self.kills.fill(0);
+        self.deaths.fill(0);
+        self.winner = None;
+        self.round += 1;
+    }
+
+    pub fn get_round(&self) -> u32 {
+        self.round
+    }
+
+    pub fn get_kill_limit(&self) -> u32 {
+        self.kill_limit
+    }
+
+    pub fn get_kills(&self, player: usize) -> i32 {
+        self.kills[player]
+    }
+
+    pub fn get_deaths(&self, player: usize) -> u32 {
+        self.deaths[player]
+    }
+
+    pub fn get_wins(&self, player: usize) -> u32 {
+        self.wins[player]
+    }
+
+    pub fn get_winner(&self) -> Option<usize> {
+        self.winner
+    }
+
+    /// Player indices ranked by kills, fewer deaths break ties.
+    pub fn standings(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.kills.len()).collect();
+        order.sort_by_key(|p| (-self.kills[*p], self.deaths[*p]));
+        order
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_ends_at_kill_limit() {
+        let mut rounds = RoundManager::new(3, 2);
+
+        assert_eq!(rounds.on_death(1, Some(0)), None);
+        assert_eq!(rounds.on_death(0, None), None);
+        assert_eq!(rounds.get_kills(0), 0);
+        assert_eq!(rounds.on_death(2, Some(1)), None);
+        assert_eq!(rounds.on_death(2, Some(1)), Some(1));
+
+        // deaths after the round is decided do not count
+        assert_eq!(rounds.on_death(1, Some(0)), Some(1));
+        assert_eq!(rounds.get_deaths(1), 1);
+        assert_eq!(rounds.standings(), vec![1, 0, 2]);
+
+        rounds.next_round();
+        assert_eq!(rounds.get_round(), 2);
+        assert_eq!(rounds.get_winner(), None);
+        assert_eq!(rounds.get_kills(1), 0);
+        assert_eq!(rounds.get_wins(1), 1);
+    }
+}
@@ -0,0 +1,593 @@
+﻿use std::path::PathBuf;
+use std::rc::Rc;
+use egui::Context;
+use vek::Vec2;
+use winit::event::MouseButton;
+use winit::keyboard::KeyCode;
+use crate::assetsdb_lib::c_assets_db::AssetsDB;
+use crate::assetsdb_lib::json_utils::write_json_file;
+use crate::classes::c_input::Input;
+use crate::classes::t_entity::Entity;
+use crate::collisions_lib::col_solver::seg_intersect;
+use crate::config_lib::c_config::Config;
+use crate::mesh_lib::c_mesh::{distance_to_segment, Mesh, MeshLine, WELD_EPSILON};
+use crate::render_lib::f_drawers::draw_line_thick;
+use crate::render_lib::t_screen_data::Screen;
+use crate::scenes_lib::e_scene_switch::SceneSwitch;
+use crate::scenes_lib::e_sceneid::SceneId;
+use crate::scenes_lib::t_scene::Scene;
+use crate::transform_lib::c_transform::Transform;
+
+/// Pick distance in screen pixels.
+const PICK_RADIUS: f32 = 8.0;
+const PREVIEW_SIZE: f32 = 180.0;
+/// Zoom factor per wheel line.
+const WHEEL_ZOOM: f32 = 1.15;
+const PREVIEW_MARGIN: f32 = 12.0;
+
+const COLOR_GRID: u32 = 0x1C1C2C;
+const COLOR_AXIS: u32 = 0x3A3A5A;
+const COLOR_HULL: u32 = 0x2E5A2E;
+const COLOR_LINE: u32 = 0xFFFFFF;
+const COLOR_VERTEX: u32 = 0x80C0FF;
+const COLOR_SELECTED: u32 = 0xFFD040;
+const COLOR_TEST: u32 = 0x5070A0;
+const COLOR_HIT: u32 = 0xFF4040;
+
+/// Mesh editor: vertices and segments on a snapping grid, a live `Transform` preview
+/// with a second mesh to test intersections against, and saving back to `.mesh` files.
+#[derive(Default)]
+pub struct EditorScene {
+    entities: Vec<Box<dyn Entity>>,
+    bounds: Vec2<usize>,
+
+    meshes: Vec<(PathBuf, Rc<Mesh>)>,
+    current: usize,
+    test_mesh: usize,
+
+    name: String,
+    path: PathBuf,
+    filled: bool,
+    vertices: Vec<Vec2<f32>>,
+    segments: Vec<(usize, usize)>,
+
+    selected: Option<usize>,
+    dragging: bool,
+
+    grid: f32,
+    snap: bool,
+    zoom: f32,
+    pan: Vec2<f32>,
+
+    preview_rotation: f32,
+    preview_spin: f32,
+    preview_scale: f32,
+    test_offset: Vec2<f32>,
+    intersections: usize,
+    show_hull: bool,
+    simplify_tolerance: f32,
+
+    status: String,
+}
+
+impl EditorScene {
+    fn open_mesh(&mut self, index: usize) {
+        let Some((path, mesh)) = self.meshes.get(index).cloned() else {
+            return;
+        };
+        self.current = index;
+        self.path = path;
+        self.load_geometry(&mesh);
+        self.fit_view();
+        self.status = format!("opened {}", self.path.display());
+    }
+
+    fn load_geometry(&mut self, mesh: &Mesh) {
+        self.name = mesh.get_name().clone();
+        self.filled = mesh.is_filled();
+        self.vertices.clear();
+        self.segments.clear();
+        self.selected = None;
+        self.dragging = false;
+
+        for line in mesh.get_lines() {
+            let a = self.weld_vertex(line.start);
+            let b = self.weld_vertex(line.end);
+            self.add_segment(a, b);
+        }
+    }
+
+    fn weld_vertex(&mut self, p: Vec2<f32>) -> usize {
+        match self.vertices.iter().position(|v| v.distance(p) <= WELD_EPSILON) {
+            Some(i) => i,
+            None => {
+                self.vertices.push(p);
+                self.vertices.len() - 1
+            }
+        }
+    }
+
+    fn add_segment(&mut self, a: usize, b: usize) {
+        let exists = self.segments.iter().any(|s| *s == (a, b) || *s == (b, a));
+        if a != b && !exists {
+            self.segments.push((a, b));
+        }
+    }
+
+    fn delete_vertex(&mut self, index: usize) {
+        self.vertices.remove(index);
+        self.segments.retain(|(a, b)| *a != index && *b != index);
+        for (a, b) in self.segments.iter_mut() {
+            if *a > index { *a -= 1; }
+            if *b > index { *b -= 1; }
+        }
+        self.selected = None;
+    }
+
+    fn to_mesh(&self) -> Mesh {
+        let lines = self.segments.iter()
+            .map(|(a, b)| MeshLine::new(self.vertices[*a], self.vertices[*b]))
+            .collect();
+        Mesh::new(self.name.clone(), lines, self.filled)
+    }
+
+    /// Runs a whole-mesh operation and reloads the edited geometry from its result.
+    fn apply(&mut self, status: &str, op: impl FnOnce(&Mesh) -> Mesh) {
+        let mesh = op(&self.to_mesh());
+        self.load_geometry(&mesh);
+        self.status = status.to_string();
+    }
+
+    fn fit_view(&mut self) {
+        let Some(aabb) = self.to_mesh().aabb() else {
+            self.zoom = 4.0;
+            self.pan = Vec2::zero();
+            return;
+        };
+        let extent = aabb.size().reduce_partial_max().max(1.0);
+        self.zoom = (self.bounds.x.min(self.bounds.y) as f32 * 0.7 / extent).clamp(0.1, 40.0);
+        self.pan = -aabb.center() * self.zoom;
+    }
+
+    /// Zooms keeping the mesh point under `screen` in place.
+    fn zoom_at(&mut self, screen: Vec2<f32>, factor: f32) {
+        let zoom = (self.zoom * factor).clamp(0.1, 40.0);
+        let anchor = (screen - self.canvas_origin()) / self.zoom;
+        self.zoom = zoom;
+        self.pan += screen - self.canvas_origin() - anchor * zoom;
+    }
+
+    fn canvas_origin(&self) -> Vec2<f32> {
+        Vec2::new(self.bounds.x as f32, self.bounds.y as f32) / 2.0 + self.pan
+    }
+
+    fn to_screen(&self, p: Vec2<f32>) -> Vec2<f32> {
+        self.canvas_origin() + p * self.zoom
+    }
+
+    fn to_mesh_space(&self, screen: Vec2<f32>) -> Vec2<f32> {
+        let p = (screen - self.canvas_origin()) / self.zoom;
+        if self.snap && self.grid > 0.0 {
+            (p / self.grid).round() * self.grid
+        } else {
+            p
+        }
+    }
+
+    fn preview_center(&self) -> Vec2<f32> {
+        Vec2::new(self.bounds.x as f32, self.bounds.y as f32) - Vec2::broadcast(PREVIEW_MARGIN + PREVIEW_SIZE / 2.0)
+    }
+
+    fn in_preview(&self, p: Vec2<f32>) -> bool {
+        let d = p - self.preview_center();
+        d.x.abs() <= PREVIEW_SIZE / 2.0 && d.y.abs() <= PREVIEW_SIZE / 2.0
+    }
+
+    fn pick_vertex(&self, screen: Vec2<f32>) -> Option<usize> {
+        self.vertices.iter()
+            .enumerate()
+            .map(|(i, v)| (i, self.to_screen(*v).distance(screen)))
+            .filter(|(_, d)| *d <= PICK_RADIUS)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    }
+
+    fn pick_segment(&self, screen: Vec2<f32>) -> Option<usize> {
+        self.segments.iter()
+            .position(|(a, b)| distance_to_segment(screen, self.to_screen(self.vertices[*a]), self.to_screen(self.vertices[*b])) <= PICK_RADIUS)
+    }
+
+    fn on_left_press(&mut self, cursor: Vec2<f32>, shift: bool) {
+        match (self.pick_vertex(cursor), self.selected) {
+            (Some(hit), Some(selected)) if shift => {
+                self.add_segment(selected, hit);
+                self.selected = Some(hit);
+            }
+            (Some(hit), _) => {
+                self.selected = Some(hit);
+                self.dragging = true;
+            }
+            (None, selected) => {
+                // clicking empty space extends the selected vertex into a polyline
+                self.vertices.push(self.to_mesh_space(cursor));
+                let new = self.vertices.len() - 1;
+                if let Some(selected) = selected {
+                    self.add_segment(selected, new);
+                }
+                self.selected = Some(new);
+            }
+        }
+    }
+
+    fn on_right_press(&mut self, cursor: Vec2<f32>) {
+        if let Some(vertex) = self.pick_vertex(cursor) {
+            self.delete_vertex(vertex);
+        } else if let Some(segment) = self.pick_segment(cursor) {
+            self.segments.remove(segment);
+        } else {
+            self.selected = None;
+        }
+    }
+
+    /// Overwrites the opened `.mesh` file. SVG imports and new meshes are written as `<name>.mesh` next to them.
+    fn save(&mut self) {
+        if self.name.trim().is_empty() {
+            self.status = "name is empty".to_string();
+            return;
+        }
+
+        let in_place = self.path.extension().is_some_and(|e| e == "mesh") && self.path.exists();
+        let path = if in_place {
+            self.path.clone()
+        } else {
+            self.path.with_file_name(format!("{}.mesh", self.name.trim()))
+        };
+
+        let mesh = self.to_mesh();
+        if let Err(e) = write_json_file(&path, &mesh) {
+            self.status = format!("saving {} failed: {}", path.display(), e);
+            return;
+        }
+
+        let mesh = Rc::new(mesh);
+        match self.meshes.iter().position(|(p, _)| *p == path) {
+            Some(i) => self.meshes[i].1 = mesh,
+            None => self.meshes.push((path.clone(), mesh)),
+        }
+        self.current = self.meshes.iter().position(|(p, _)| *p == path).unwrap_or(0);
+        self.status = format!("saved {}", path.display());
+        self.path = path;
+    }
+
+    fn new_mesh(&mut self) {
+        self.path = AssetsDB::root_folder().join("models").join("new_mesh.mesh");
+        self.load_geometry(&Mesh::new("new_mesh".to_string(), vec![], false));
+        self.zoom = 4.0;
+        self.pan = Vec2::zero();
+        self.status = "new mesh".to_string();
+    }
+
+    /// Transforms used by the preview box for the edited and the test mesh.
+    fn preview_transforms(&self, edited: &Mesh, test: &Mesh) -> (Transform, Transform) {
+        let radius = edited.bounding_radius().max(test.bounding_radius()).max(1.0);
+        let fit = PREVIEW_SIZE * 0.3 / radius;
+        let center = self.preview_center();
+
+        let edited_transform = Transform::new(center, Vec2::broadcast(fit * self.preview_scale), self.preview_rotation, self.bounds);
+        let test_transform = Transform::new(center + self.test_offset * PREVIEW_SIZE / 2.0, Vec2::broadcast(fit), 0.0, self.bounds);
+        (edited_transform, test_transform)
+    }
+
+    fn draw_grid(&self, screen: &mut Screen) {
+        let w = self.bounds.x as f32;
+        let h = self.bounds.y as f32;
+
+        let mut step = self.grid.max(0.01) * self.zoom;
+        while step < 6.0 {
+            step *= 5.0;
+        }
+
+        let origin = self.canvas_origin();
+        let mut x = origin.x.rem_euclid(step);
+        while x < w {
+            draw_line_thick(screen, x, 0.0, x, h - 1.0, 1, COLOR_GRID);
+            x += step;
+        }
+        let mut y = origin.y.rem_euclid(step);
+        while y < h {
+            draw_line_thick(screen, 0.0, y, w - 1.0, y, 1, COLOR_GRID);
+            y += step;
+        }
+
+        draw_line_thick(screen, origin.x, 0.0, origin.x, h - 1.0, 1, COLOR_AXIS);
+        draw_line_thick(screen, 0.0, origin.y, w - 1.0, origin.y, 1, COLOR_AXIS);
+    }
+
+    fn draw_preview(&self, screen: &mut Screen, edited: &Mesh) {
+        let test = self.meshes.get(self.test_mesh).map(|(_, m)| m.clone()).unwrap_or_default();
+        let (edited_transform, test_transform) = self.preview_transforms(edited, &test);
+
+        let c = self.preview_center();
+        let half = PREVIEW_SIZE / 2.0;
+        let corners = [Vec2::new(-half, -half), Vec2::new(half, -half), Vec2::new(half, half), Vec2::new(-half, half)];
+        for i in 0..4 {
+            let a = c + corners[i];
+            let b = c + corners[(i + 1) % 4];
+            draw_line_thick(screen, a.x, a.y, b.x, b.y, 1, COLOR_AXIS);
+        }
+
+        let world = |mesh: &Mesh, t: &Transform| -> Vec<(Vec2<f32>, Vec2<f32>)> {
+            mesh.get_lines().iter()
+                .map(|l| (t.transform_point_to_world(l.start), t.transform_point_to_world(l.end)))
+                .collect()
+        };
+        let edited_world = world(edited, &edited_transform);
+        let test_world = world(&test, &test_transform);
+
+        for (a, b) in test_world.iter() {
+            draw_line_thick(screen, a.x, a.y, b.x, b.y, 1, COLOR_TEST);
+        }
+        for (a, b) in edited_world.iter() {
+            let hit = test_world.iter().any(|(c, d)| seg_intersect(*a, *b, *c, *d));
+            draw_line_thick(screen, a.x, a.y, b.x, b.y, 2, if hit { COLOR_HIT } else { COLOR_LINE });
+        }
+    }
+
+    fn count_intersections(&self) -> usize {
+        let edited = self.to_mesh();
+        let test = self.meshes.get(self.test_mesh).map(|(_, m)| m.clone()).unwrap_or_default();
+        let (edited_transform, test_transform) = self.preview_transforms(&edited, &test);
+
+        let mut count = 0;
+        for l1 in edited.get_lines() {
+            let a = edited_transform.transform_point_to_world(l1.start);
+            let b = edited_transform.transform_point_to_world(l1.end);
+            for l2 in test.get_lines() {
+                let c = test_transform.transform_point_to_world(l2.start);
+                let d = test_transform.transform_point_to_world(l2.end);
+                if seg_intersect(a, b, c, d) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+}
+
+impl Scene for EditorScene {
+    fn create_scene(&mut self, _config: &Config, screen: &Screen, assets_db: &AssetsDB) {
+        self.bounds = Vec2::new(screen.width(), screen.height());
+        self.meshes = assets_db.get_meshes();
+
+        self.grid = 5.0;
+        self.snap = true;
+        self.preview_scale = 1.0;
+        self.preview_spin = 0.5;
+        self.test_offset = Vec2::new(0.5, 0.0);
+        self.simplify_tolerance = 1.0;
+
+        let first = self.meshes.iter().position(|(_, m)| m.get_name() == "player").unwrap_or(0);
+        self.test_mesh = self.meshes.iter().position(|(_, m)| m.get_name() == "bullet").unwrap_or(0);
+        if self.meshes.is_empty() {
+            self.new_mesh();
+        } else {
+            self.open_mesh(first);
+        }
+    }
+
+    fn get_scene_name(&self) -> String {
+        String::from("EditorScene")
+    }
+
+    fn update(&mut self, dt: f32, input: &Input, _config: &Config, _assets_db: &AssetsDB) -> SceneSwitch {
+        self.preview_rotation += self.preview_spin * dt;
+
+        let cursor = input.get_cursor();
+        let shift = input.is_key_down(KeyCode::ShiftLeft) || input.is_key_down(KeyCode::ShiftRight);
+
+        if let Some(c) = cursor.filter(|c| !self.in_preview(*c)) {
+            if input.is_mouse_pressed(MouseButton::Left) {
+                self.on_left_press(c, shift);
+            }
+            if input.is_mouse_pressed(MouseButton::Right) {
+                self.on_right_press(c);
+            }
+            if input.get_wheel().y != 0.0 {
+                self.zoom_at(c, WHEEL_ZOOM.powf(input.get_wheel().y));
+            }
+        }
+
+        if input.is_mouse_released(MouseButton::Left) {
+            self.dragging = false;
+        }
+        if self.dragging && let (Some(c), Some(i)) = (cursor, self.selected) {
+            self.vertices[i] = self.to_mesh_space(c);
+        }
+        if input.is_mouse_down(MouseButton::Middle) {
+            self.pan += input.get_cursor_delta();
+        }
+
+        self.intersections = self.count_intersections();
+        SceneSwitch::None
+    }
+
+    fn render(&mut self, screen: &mut Screen) {
+        self.draw_grid(screen);
+        let mesh = self.to_mesh();
+
+        if self.show_hull {
+            let hull = mesh.convex_hull();
+            for (i, a) in hull.iter().enumerate() {
+                let a = self.to_screen(*a);
+                let b = self.to_screen(hull[(i + 1) % hull.len()]);
+                draw_line_thick(screen, a.x, a.y, b.x, b.y, 1, COLOR_HULL);
+            }
+        }
+
+        for (a, b) in self.segments.iter() {
+            let a = self.to_screen(self.vertices[*a]);
+            let b = self.to_screen(self.vertices[*b]);
+            draw_line_thick(screen, a.x, a.y, b.x, b.y, 2, COLOR_LINE);
+        }
+
+        for (i, v) in self.vertices.iter().enumerate() {
+            let p = self.to_screen(*v);
+            let color = if self.selected == Some(i) { COLOR_SELECTED } else { COLOR_VERTEX };
+            draw_line_thick(screen, p.x - 2.0, p.y, p.x + 2.0, p.y, 5, color);
+        }
+
+        self.draw_preview(screen, &mesh);
+    }
+
+    fn ui(&mut self, ctx: &Context) -> SceneSwitch {
+        let mut action = SceneSwitch::None;
+
+        egui::Window::new("Mesh editor")
+            .default_pos([8.0, 8.0])
+            .default_width(230.0)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let names: Vec<String> = self.meshes.iter().map(|(p, _)| p.file_name().unwrap_or_default().to_string_lossy().to_string()).collect();
+
+                let mut current = self.current;
+                egui::ComboBox::from_label("mesh")
+                    .selected_text(names.get(current).cloned().unwrap_or_default())
+                    .show_ui(ui, |ui| {
+                        for (i, name) in names.iter().enumerate() {
+                            ui.selectable_value(&mut current, i, name);
+                        }
+                    });
+                if current != self.current {
+                    self.open_mesh(current);
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label("name");
+                    ui.text_edit_singleline(&mut self.name);
+                });
+                ui.checkbox(&mut self.filled, "filled");
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.snap, "snap");
+                    ui.add(egui::Slider::new(&mut self.grid, 0.5..=20.0).text("grid"));
+                });
+                ui.add(egui::Slider::new(&mut self.zoom, 0.1..=40.0).logarithmic(true).text("zoom"));
+
+                let mesh = self.to_mesh();
+                let size = mesh.aabb().map(|b| b.size()).unwrap_or_default();
+                let centroid = mesh.centroid();
+                ui.label(format!("{} vertices, {} segments", self.vertices.len(), self.segments.len()));
+                ui.label(format!("size {:.1} x {:.1}, area {:.1}", size.x, size.y, mesh.area()));
+                ui.label(format!("centroid {:.1}, {:.1}", centroid.x, centroid.y));
+
+                ui.separator();
+                ui.horizontal_wrapped(|ui| {
+                    if ui.button("Recenter").clicked() {
+                        self.apply("recentered", |m| m.recentered());
+                    }
+                    if ui.button("Mirror X").clicked() {
+                        self.apply("mirrored x", |m| m.mirrored(true, false));
+                    }
+                    if ui.button("Mirror Y").clicked() {
+                        self.apply("mirrored y", |m| m.mirrored(false, true));
+                    }
+                    if ui.button("x2").clicked() {
+                        self.apply("scaled x2", |m| m.scaled(Vec2::broadcast(2.0)));
+                    }
+                    if ui.button("x0.5").clicked() {
+                        self.apply("scaled x0.5", |m| m.scaled(Vec2::broadcast(0.5)));
+                    }
+                    if ui.button("Fit").clicked() {
+                        self.fit_view();
+                    }
+                });
+                ui.horizontal(|ui| {
+                    let tolerance = self.simplify_tolerance;
+                    if ui.button("Simplify").clicked() {
+                        self.apply("simplified", |m| m.simplified(tolerance));
+                    }
+                    ui.add(egui::Slider::new(&mut self.simplify_tolerance, 0.1..=10.0).text("tol"));
+                });
+                ui.checkbox(&mut self.show_hull, "show convex hull");
+
+                ui.separator();
+                ui.label("preview");
+                ui.add(egui::Slider::new(&mut self.preview_spin, -3.0..=3.0).text("spin"));
+                ui.add(egui::Slider::new(&mut self.preview_scale, 0.2..=2.0).text("scale"));
+
+                let mut test = self.test_mesh;
+                egui::ComboBox::from_label("test")
+                    .selected_text(names.get(test).cloned().unwrap_or_default())
+                    .show_ui(ui, |ui| {
+                        for (i, name) in names.iter().enumerate() {
+                            ui.selectable_value(&mut test, i, name);
+                        }
+                    });
+                self.test_mesh = test;
+                ui.add(egui::Slider::new(&mut self.test_offset.x, -1.0..=1.0).text("test x"));
+                ui.add(egui::Slider::new(&mut self.test_offset.y, -1.0..=1.0).text("test y"));
+                ui.label(format!("intersecting segment pairs: {}", self.intersections));
+                if ui.button("Merge test mesh").clicked() {
+                    let other = self.meshes.get(self.test_mesh).map(|(_, m)| m.clone()).unwrap_or_default();
+                    self.apply("merged", |m| m.merged(&other));
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Save").clicked() {
+                        self.save();
+                    }
+                    if ui.button("New").clicked() {
+                        self.new_mesh();
+                    }
+                    if ui.button("Menu").clicked() {
+                        action = SceneSwitch::Switch(SceneId::Menu);
+                    }
+                });
+                ui.label(&self.status);
+                ui.small("LMB select / drag, LMB on empty adds a vertex linked to the selection, Shift+LMB links two vertices, RMB deletes, MMB pans, wheel zooms");
+            });
+
+        action
+    }
+
+    fn get_entities(&self) -> &Vec<Box<dyn Entity>> {
+        &self.entities
+    }
+
+    fn get_entities_mut(&mut self) -> &mut Vec<Box<dyn Entity>> {
+        &mut self.entities
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square() -> Mesh {
+        let p = [Vec2::new(-1.0, -1.0), Vec2::new(1.0, -1.0), Vec2::new(1.0, 1.0), Vec2::new(-1.0, 1.0)];
+        let lines = (0..4).map(|i| MeshLine::new(p[i], p[(i + 1) % 4])).collect();
+        Mesh::new("square".to_string(), lines, false)
+    }
+
+    #[test]
+    fn editor_welds_shared_endpoints() {
+        let mut editor = EditorScene::default();
+        editor.load_geometry(&square());
+
+        assert_eq!(editor.vertices.len(), 4);
+        assert_eq!(editor.segments.len(), 4);
+        assert_eq!(editor.to_mesh().get_lines().len(), 4);
+    }
+
+    #[test]
+    fn editor_delete_vertex_reindexes_segments() {
+        let mut editor = EditorScene::default();
+        editor.load_geometry(&square());
+        editor.delete_vertex(0);
+
+        assert_eq!(editor.vertices.len(), 3);
+        assert_eq!(editor.segments, vec![(0, 1), (1, 2)]);
+    }
+}
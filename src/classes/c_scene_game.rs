@@ -1,10 +1,16 @@
-﻿use std::rc::Rc;
-use egui::{vec2, Align, Context, Layout};
-use rand::prelude::ThreadRng;
-use rand::{rng, Rng};
+﻿use std::hash::{DefaultHasher, Hash};
+use std::rc::Rc;
+use egui::Context;
+use rand::Rng;
 use crate::assetsdb_lib::c_assets_db::AssetsDB;
 use crate::classes::c_asteroid_entity::AsteroidEntity;
-use crate::classes::c_player_entity::PlayerEntity;
+use crate::classes::c_input::Input;
+use crate::classes::c_menu_nav::MenuNav;
+use crate::classes::c_player_entity::{PlayerEntity, PLAYER_COLORS};
+use crate::classes::e_action::Action;
+use crate::classes::c_round_manager::{RoundManager, DEFAULT_KILL_LIMIT};
+use crate::classes::e_game_mode::GameMode;
+use crate::collisions_lib::c_collision_rules::CollisionRules;
 use crate::classes::t_entity::Entity;
 use crate::config_lib::c_config::Config;
 use crate::render_lib::t_screen_data::Screen;
@@ -16,61 +22,171 @@ use crate::classes::c_audio_context::AudioContext;
 use crate::classes::c_debris_entity::DebrisEntity;
 use crate::classes::c_enemy_entity::EnemyEntity;
 use crate::collisions_lib::e_col_layers::ColLayer;
+use crate::mesh_lib::c_asteroid_generator::{generate_asteroid, AsteroidShape};
+use crate::mesh_lib::c_fracture::fracture_mesh;
 use crate::mesh_lib::c_mesh::Mesh;
-use crate::render_lib::f_drawers::{ui_button, ui_draw_icon, ui_get_card_rect, ui_title_rect, ui_transparent_frame};
+use crate::classes::c_score_popup_entity::ScorePopupEntity;
+use crate::font_lib::c_stroke_font::StrokeFont;
+use crate::render_lib::c_background::{Background, BackgroundSettings};
+use crate::render_lib::c_particle_effect::ParticleEffect;
+use crate::render_lib::c_particle_system::ParticleSystem;
+use crate::render_lib::e_render_layer::RenderLayer;
+use crate::render_lib::f_drawers::{draw_mesh, draw_text, ui_get_card_rect, ui_title_rect, ui_transparent_frame};
 use crate::scenes_lib::e_scene_event::SceneEvent;
 use crate::scenes_lib::e_scene_switch::SceneSwitch;
 use crate::scenes_lib::e_sceneid::SceneId;
-use crate::sprite_lib::c_sprite::SpriteTex;
 use crate::web_lib::c_web_client::WebClient;
+use crate::random_lib::c_sim_rng::sim_rng;
+
+/// Fragments with a smaller area-preserving scale turn into shards instead of asteroids.
+const MIN_ASTEROID_SIZE: f32 = 0.3;
+/// Seconds the results stay up in an online match before the next round starts on both ends.
+const ONLINE_RESULTS_TIME: f32 = 5.0;
 
 #[derive(Default)]
 pub enum GameState{
     #[default]
     Active,
-    End
+    End,
+    /// A versus round was decided, the results screen is up and the field is frozen.
+    Results
+}
+
+/// Lives and score of one local player.
+struct PlayerState {
+    /// Ship entity, `None` once the player ran out of lives.
+    entity_id: Option<u32>,
+    lives: i8,
+    score: u32,
 }
 
 #[derive(Default)]
 pub struct GameScene {
     entities: Vec<Box<dyn Entity>>,
 
+    mode: GameMode,
+    players: Vec<PlayerState>,
+    /// Kills and rounds of a versus match.
+    rounds: Option<RoundManager>,
+    /// Set by the results screen, the next update resets the field.
+    start_round: bool,
+    /// Online match: every update is a lockstep tick, so the local UI must not change the
+    /// simulation. Pause is off and rounds advance on a timer instead of a button.
+    lockstep: bool,
+    results_timer: f32,
+    /// Attract-mode game behind the menu: silent, and it starts over instead of ending.
+    demo: bool,
     asteroids_ids: Vec<u32>,
-    player_id: u32,
-    asteroids_models: Vec<Rc<Mesh>>,
-    debris_models: Vec<Rc<Mesh>>,
     web_client: WebClient,
 
 
     state: GameState,
-    player_healths: i8,
-    scores: u32,
-    health_icon: Rc<SpriteTex>,
+    nav: MenuNav,
+    font: Rc<StrokeFont>,
+    player_mesh: Rc<Mesh>,
+    thrust_effect: Rc<ParticleEffect>,
 
     enemy_timer: f32,
 
     asteroids_count: u32,
+    level: u32,
+
+    backgrounds: Vec<Rc<BackgroundSettings>>,
+    background: Background,
+
+    particles: ParticleSystem,
+    explosion_effect: Rc<ParticleEffect>,
 
     audio_context: AudioContext
 }
 
 impl GameScene {
+    pub fn new(mode: GameMode) -> Self {
+        Self { mode, ..Default::default() }
+    }
+
+    pub fn new_lockstep(mode: GameMode) -> Self {
+        Self { mode, lockstep: true, ..Default::default() }
+    }
+
+    pub fn new_demo() -> Self {
+        Self { mode: GameMode::Solo, demo: true, ..Default::default() }
+    }
+
+    /// Fresh field for a new game: first level, full lives, ships on their spawn points.
+    fn start_game(&mut self, config: &Config) {
+        self.entities.clear();
+        self.asteroids_ids.clear();
+        self.players.clear();
+        self.particles = ParticleSystem::default();
+        self.enemy_timer = 0.0;
+
+        self.asteroids_count = 5;
+        self.set_level(1, config);
+        for index in 0..self.mode.player_count() {
+            let entity_id = self.spawn_player(index, config);
+            self.players.push(PlayerState { entity_id: Some(entity_id), lives: 3, score: 0 });
+        }
+        self.spawn_asteroids(config);
+        self.state = GameState::Active;
+    }
+
+    /// Solo starts in the middle and co-op players are spread along the horizontal center line.
+    /// Versus ships sit on a ring facing the center, the first player on the left.
+    fn spawn_pose(&self, index: usize, config: &Config) -> (Vec2<f32>, f32) {
+        let count = self.mode.player_count();
+        let center = Vec2::new(config.x() as f32, config.y() as f32) / 2.0;
+
+        if let GameMode::Versus { .. } = self.mode {
+            let angle = std::f32::consts::PI + index as f32 * std::f32::consts::TAU / count as f32;
+            let radius = center.x.min(center.y) * 0.7;
+            let pos = center + Vec2::new(angle.cos(), angle.sin()) * radius;
+            return (pos, angle + std::f32::consts::PI);
+        }
+
+        (Vec2::new(config.x() as f32 * (index + 1) as f32 / (count + 1) as f32, center.y), 0.0)
+    }
+
+    fn spawn_player(&mut self, index: usize, config: &Config) -> u32 {
+        let (pos, rotation) = self.spawn_pose(index, config);
+        let mut player = PlayerEntity::new(
+            index,
+            Transform::new(
+                pos,
+                Vec2::new(0.3, 0.3),
+                rotation,
+                config.size()
+            ),
+            self.player_mesh.clone(),
+            self.thrust_effect.clone()
+        );
+        if self.mode == GameMode::Solo {
+            player.set_respawn_delay(0.0);
+        }
+        self.add_entity(Box::new(player))
+    }
+
+    /// Ships still in play.
+    fn player_entities(&self) -> impl Iterator<Item = &Box<dyn Entity>> {
+        let ids: Vec<u32> = self.players.iter().filter_map(|p| p.entity_id).collect();
+        self.entities.iter().filter(move |e| ids.contains(&e.get_entity_id()))
+    }
+
     pub fn spawn_asteroids(&mut self, config: &Config) {
-        let mut rng = rand::rng();
+        let mut rng = sim_rng();
 
 
         let asteroid_radius = 120.0;
-        let player_entity = self.entities.iter().find(|x| x.get_entity_id() == self.player_id).unwrap();
+        let mut safe_points: Vec<Vec2<f32>> = self.player_entities().map(|e| *e.get_position()).collect();
+        safe_points.extend((0..self.players.len()).map(|i| self.spawn_pose(i, config).0));
 
-        let player_pos: Vec2<f32> = player_entity.get_position().clone();
         while self.asteroids_ids.len() < self.asteroids_count as usize {
             let random_pos = Vec2::new(
                 rng.random_range(0..config.x()) as f32,
                 rng.random_range(0..config.y()) as f32
             );
 
-            let dist = Vec2::distance(player_pos, random_pos);
-            if (dist >= asteroid_radius) {
+            if safe_points.iter().all(|p| Vec2::distance(*p, random_pos) >= asteroid_radius) {
 
                 let max_scale = 1.0;
                 self.spawn_asteroid(config, &mut rng, random_pos, 0.8, max_scale);
@@ -78,17 +194,13 @@ impl GameScene {
         }
     }
 
-    pub fn spawn_asteroid(&mut self, config: &Config, mut rng: &mut ThreadRng, random_pos: Vec2<f32>,min_scale: f32, max_scale: f32) {
+    pub fn spawn_asteroid(&mut self, config: &Config, rng: &mut impl Rng, random_pos: Vec2<f32>,min_scale: f32, max_scale: f32) {
 
 
 
         let scale = rng.random_range(min_scale..max_scale);
 
-        let mesh: Rc<Mesh> = self.asteroids_models
-            .iter()
-            .choose(&mut rng)
-            .cloned()
-            .unwrap();
+        let mesh = Rc::new(generate_asteroid(rng.random(), &AsteroidShape::for_scale(scale)));
 
 
         let mut asteroid = AsteroidEntity::new(
@@ -114,6 +226,39 @@ impl GameScene {
         self.asteroids_ids.push(id);
     }
 
+    /// Cuts the destroyed outline into pieces. Big pieces keep flying as asteroids,
+    /// the rest become short-lived shards.
+    pub fn fracture_asteroid(&mut self, mesh: &Mesh, transform: &Transform, impact: Vec2<f32>, size: f32) {
+        let mut rng = sim_rng();
+
+        let chords = rng.random_range(1..3);
+        let fragments = fracture_mesh(mesh, transform.inverse_transform_point(impact), chords, &mut rng);
+
+        for fragment in fragments {
+            let piece_size = size * fragment.area_scale();
+            let pos = transform.transform_point_to_world(fragment.offset);
+
+            let push = (pos - impact).try_normalized().unwrap_or(Vec2::unit_x()) * rng.random_range(30.0..90.0);
+            let velocity = *transform.get_velocity() + push;
+
+            let mut piece_transform = *transform;
+            piece_transform.update_position_warp(pos);
+
+            if piece_size >= MIN_ASTEROID_SIZE {
+                let mut asteroid = AsteroidEntity::new(piece_transform, Rc::new(fragment.mesh));
+                asteroid.set_velocity(velocity);
+                asteroid.set_size(piece_size);
+
+                let id = self.add_entity(Box::new(asteroid));
+                self.asteroids_ids.push(id);
+            } else {
+                let mut shard = DebrisEntity::new(piece_transform, Rc::new(fragment.mesh));
+                shard.set_velocity(velocity * 1.5);
+                self.add_entity(Box::new(shard));
+            }
+        }
+    }
+
     pub fn spawn_enemy(&mut self, pos: Vec2<f32>, config: &Config, assets_db: &AssetsDB){
         let mut enemy = EnemyEntity::new(
             Transform::new(
@@ -134,7 +279,7 @@ impl GameScene {
             let mut is_can_spawn = true;
             let min_dist = 400.0;
 
-            let mut rng = rand::rng();
+            let mut rng = sim_rng();
             let random_pos = Vec2::new(
                 rng.random_range(100..(config.x()-100)) as f32,
                 rng.random_range(100..(config.y() - 100)) as f32
@@ -155,35 +300,146 @@ impl GameScene {
     }
 
 
-    pub fn spawn_debris(&mut self, pos: Vec2<f32>, config: &Config, assets_db: &AssetsDB){
-        let mut rand = rand::rng();
+    /// Level backdrops cycle through `level_01.bg`, `level_02.bg`, ... with a new seed each round.
+    fn set_level(&mut self, level: u32, config: &Config) {
+        self.level = level;
+        if self.backgrounds.is_empty() {
+            return;
+        }
 
-        let count = rand.random_range(3..6);
+        let settings = &self.backgrounds[(level as usize - 1) % self.backgrounds.len()];
+        self.background = Background::new(settings, config.size(), level as u64);
+    }
 
+    /// One row per player: score on the left, lives on the right, in the ship colour.
+    fn draw_hud(&self, screen: &mut Screen) {
+        let row_step = 44.0;
+        let icon_step = 26.0;
 
+        for (index, player) in self.players.iter().enumerate() {
+            let color = PLAYER_COLORS[index];
+            let y = index as f32 * row_step;
 
+            // versus counts kills towards the limit instead of points and lives
+            if let Some(rounds) = &self.rounds {
+                let text = format!("{}/{}", rounds.get_kills(index), rounds.get_kill_limit());
+                draw_text(screen, &self.font, &text, Vec2::new(16.0, 12.0 + y), 4.0, 0.0, color);
+                continue;
+            }
 
-        for i in 0..count {
+            draw_text(screen, &self.font, &format!("{:06}", player.score), Vec2::new(16.0, 12.0 + y), 4.0, 0.0, color);
 
-            let mesh: Rc<Mesh> = self.debris_models
-                .iter()
-                .choose(&mut rand)
-                .cloned()
-                .unwrap();
+            for i in 0..player.lives.max(0) {
+                let icon = Transform::new(
+                    Vec2::new(screen.width() as f32 - 24.0 - i as f32 * icon_step, 24.0 + y),
+                    Vec2::new(0.1, 0.1),
+                    -std::f32::consts::FRAC_PI_2,
+                    Vec2::new(screen.width(), screen.height())
+                );
+                draw_mesh(screen, &icon, &self.player_mesh, color);
+            }
+        }
+    }
 
-            let val = DebrisEntity::new(
-                Transform::new(
-                    pos,
-                    Vec2::new(1.0, 1.0) * rand.random_range(0.25..1.0),
-                    rand.random_range(-360.0..360.0),
-                    config.size()
-                ),
-                mesh
-            );
+    /// In co-op a player who ran out of lives comes back with one life when a wave is cleared.
+    fn rejoin_players(&mut self, config: &Config) {
+        if self.mode != GameMode::Coop {
+            return;
+        }
+
+        for index in 0..self.players.len() {
+            if self.players[index].entity_id.is_none() {
+                let entity_id = self.spawn_player(index, config);
+                let player = &mut self.players[index];
+                player.entity_id = Some(entity_id);
+                player.lives = 1;
+            }
+        }
+    }
 
+    /// Clears the field for the next versus round: fresh asteroids, ships back on their spawn points.
+    fn next_round(&mut self, config: &Config) {
+        if let Some(rounds) = &mut self.rounds {
+            rounds.next_round();
+        }
 
-            self.add_entity(Box::new(val));
+        self.entities.clear();
+        self.asteroids_ids.clear();
+        self.particles = ParticleSystem::default();
+        for index in 0..self.players.len() {
+            let entity_id = self.spawn_player(index, config);
+            self.players[index].entity_id = Some(entity_id);
         }
+        self.spawn_asteroids(config);
+        self.state = GameState::Active;
+    }
+
+    fn draw_results(&mut self, ctx: &Context) -> SceneSwitch {
+        let Some(rounds) = &self.rounds else {
+            return SceneSwitch::None;
+        };
+
+        let frame = ui_transparent_frame();
+        let mut scene_switch = SceneSwitch::None;
+
+        self.nav.begin(ctx);
+        if self.nav.take_back() {
+            scene_switch = SceneSwitch::Switch(SceneId::Menu);
+        }
+
+        egui::CentralPanel::default().frame(frame).show(ctx, |ui| {
+            let (avail, _) = ui_title_rect(ui);
+            let (card_rect, card_frame) = ui_get_card_rect(avail);
+
+            ui.allocate_ui_at_rect(card_rect, |ui| {
+                card_frame.show(ui, |ui| {
+                    ui.vertical_centered(|ui| {
+                        let winner = rounds.get_winner().map(|p| format!("PLAYER {} WINS", p + 1)).unwrap_or_default();
+                        ui.label(egui::RichText::new(format!("ROUND {}\n{}", rounds.get_round(), winner)).size(48.0).strong());
+                        ui.add_space(18.0);
+
+                        egui::Grid::new("results")
+                            .num_columns(4)
+                            .spacing([32.0, 8.0])
+                            .show(ui, |ui| {
+                                for header in ["", "KILLS", "DEATHS", "ROUNDS"] {
+                                    ui.label(egui::RichText::new(header).size(18.0));
+                                }
+                                ui.end_row();
+
+                                for index in rounds.standings() {
+                                    let [_, r, g, b] = PLAYER_COLORS[index].to_be_bytes();
+                                    let color = egui::Color32::from_rgb(r, g, b);
+                                    ui.label(egui::RichText::new(format!("P{}", index + 1)).size(24.0).color(color));
+                                    for value in [rounds.get_kills(index).to_string(), rounds.get_deaths(index).to_string(), rounds.get_wins(index).to_string()] {
+                                        ui.label(egui::RichText::new(value).size(24.0).color(color));
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+
+                        ui.add_space(18.0);
+                        if self.lockstep {
+                            ui.label(egui::RichText::new(format!("Next round in {}", self.results_timer.ceil().max(0.0))).size(20.0));
+                        } else if self.nav.button(ui, "Next round") {
+                            self.start_round = true;
+                        }
+                        ui.add_space(10.0);
+                        if self.nav.button(ui, "Menu") {
+                            scene_switch = SceneSwitch::Switch(SceneId::Menu);
+                        }
+                    });
+                });
+            });
+        });
+        self.nav.end();
+
+        scene_switch
+    }
+
+    /// Leaderboard score of the session, a co-op team submits its combined points.
+    fn total_score(&self) -> u32 {
+        self.players.iter().map(|p| p.score).sum()
     }
 }
 
@@ -191,94 +447,97 @@ impl GameScene {
 impl Scene for GameScene
 {
     fn create_scene(&mut self, config: &Config, screen: &Screen, assets_db: &AssetsDB) {
-        let mut player = PlayerEntity::new(
-            Transform::new(
-                screen.center(),
-                Vec2::new(0.3, 0.3),
-                0.0,
-                config.size()
-            ),
-            assets_db.get_mesh_by_name("player").unwrap_or_default()
-        );
-
-        self.audio_context = AudioContext::new();
-        self.player_healths = 3;
-
-
-        self.asteroids_models = vec![
-            assets_db.get_mesh_by_name("asteroid_01").unwrap_or_default(),
-            assets_db.get_mesh_by_name("asteroid_02").unwrap_or_default(),
-            assets_db.get_mesh_by_name("asteroid_03").unwrap_or_default(),
-            assets_db.get_mesh_by_name("asteroid_04").unwrap_or_default()
-        ];
-
-        self.debris_models = vec![
-            assets_db.get_mesh_by_name("debris_01").unwrap_or_default(),
-            assets_db.get_mesh_by_name("debris_02").unwrap_or_default(),
-            assets_db.get_mesh_by_name("debris_03").unwrap_or_default(),
-            assets_db.get_mesh_by_name("debris_04").unwrap_or_default(),
-            assets_db.get_mesh_by_name("debris_05").unwrap_or_default(),
-            assets_db.get_mesh_by_name("debris_06").unwrap_or_default(),
-            assets_db.get_mesh_by_name("debris_07").unwrap_or_default(),
-            assets_db.get_mesh_by_name("debris_08").unwrap_or_default(),
-            assets_db.get_mesh_by_name("debris_09").unwrap_or_default(),
-            assets_db.get_mesh_by_name("debris_10").unwrap_or_default(),
-            assets_db.get_mesh_by_name("debris_11").unwrap_or_default(),
-            assets_db.get_mesh_by_name("debris_12").unwrap_or_default(),
-            assets_db.get_mesh_by_name("debris_13").unwrap_or_default(),
-            assets_db.get_mesh_by_name("debris_14").unwrap_or_default(),
-            assets_db.get_mesh_by_name("debris_15").unwrap_or_default(),
-        ];
+        self.player_mesh = assets_db.get_mesh_by_name("player").unwrap_or_default();
+        self.thrust_effect = assets_db.get_particles_by_name("thrust").unwrap_or_default();
 
+        if !self.demo {
+            self.audio_context = AudioContext::new();
+        }
 
 
         self.asteroids_count = 5;
-        self.health_icon = assets_db.get_sprite_by_name("heart").unwrap_or_default();
-        self.player_id = self.add_entity(Box::new(player));
+        self.backgrounds = (1..)
+            .map_while(|i| assets_db.get_background_by_name(&format!("level_{:02}", i)))
+            .collect();
+        self.font = assets_db.get_font_by_name("vector").unwrap_or_default();
+        self.explosion_effect = assets_db.get_particles_by_name("explosion").unwrap_or_default();
+        if let GameMode::Versus { players } = self.mode {
+            self.rounds = Some(RoundManager::new(players, DEFAULT_KILL_LIMIT));
+        }
 
-        self.spawn_asteroids(&config);
+        self.start_game(config);
     }
 
     fn custom_events_solve(&mut self, scene_event: &Vec<SceneEvent>, config: &Config, asset_db: &AssetsDB, dt: f32) {
 
 
-        self.spawn_enemy_timer(dt, config, asset_db);
+        if self.rounds.is_none() {
+            self.spawn_enemy_timer(dt, config, asset_db);
+        }
+        self.particles.update(dt);
+
+        // the backdrop drifts with the average ship so neither player is favoured
+        let velocities: Vec<Vec2<f32>> = self.player_entities().map(|e| e.get_velocity()).collect();
+        let camera_velocity = if velocities.is_empty() {
+            Vec2::zero()
+        } else {
+            velocities.iter().copied().sum::<Vec2<f32>>() / velocities.len() as f32
+        };
+        self.background.update(dt, camera_velocity);
 
         for n in scene_event {
             match n {
-                SceneEvent::DemolishAsteroid { pos, scale, id  } => {
+                SceneEvent::DemolishAsteroid { pos, scale, id, mesh, transform, impact, by } => {
 
                     self.audio_context.beep_asteroid_hit();
-
-                    self.scores += (*scale  * 50.0) as u32;
+                    self.particles.burst(&self.explosion_effect, *pos, Vec2::unit_x(), Vec2::zero());
+
+                    // in co-op only player shots score and enemy fire breaks rocks for free,
+                    // the solo player is credited with every rock
+                    let scorer = if self.mode == GameMode::Solo { Some(0) } else { *by };
+                    if self.rounds.is_none() && let Some(player) = scorer.and_then(|i| self.players.get_mut(i)) {
+                        let points = (*scale  * 50.0) as u32;
+                        player.score += points;
+                        self.add_entity(Box::new(ScorePopupEntity::new(*pos, self.font.clone(), format!("+{}", points))));
+                    }
 
                     if let Some(i) = self.asteroids_ids.iter().position(|x| *x == *id) {
                         self.asteroids_ids.remove(i); // O(n), сдвигает элементы
                     }
-                    let max_scale = scale/2.0;
-                    let min_scale = max_scale * 0.55;
-                    if (min_scale< 0.15) {continue};
 
-                    let mut rng = rand::rng();
+                    self.fracture_asteroid(mesh, transform, *impact, *scale);
 
-                    let count = rng.random_range(2..4);
+                },
+                SceneEvent::PlayerDeath { player, by } if self.rounds.is_some() => {
+                    self.audio_context.beep_death();
 
-                    for i in 0..count {
-                        self.spawn_asteroid(config, &mut rng, *pos, min_scale, max_scale);
+                    if let Some(rounds) = &mut self.rounds
+                        && rounds.on_death(*player, *by).is_some()
+                        && let GameState::Active = self.state {
+                        self.state = GameState::Results;
+                        self.results_timer = ONLINE_RESULTS_TIME;
+                        self.nav.reset();
                     }
-
-                },
-                SceneEvent::PlayerDeath =>{
+                }
+                SceneEvent::PlayerDeath { player: index, .. } =>{
                     self.audio_context.beep_death();
 
-                    self.player_healths -= 1;
-                    if (self.player_healths <= 0) {
+                    let player = &mut self.players[*index];
+                    player.lives -= 1;
+                    if player.lives <= 0 && let Some(entity_id) = player.entity_id.take() {
+                        self.remove_entity(entity_id);
+                    }
+
+                    if self.players.iter().all(|p| p.entity_id.is_none()) {
                         self.state = GameState::End;
-                        self.remove_entity(self.player_id);
+                        self.nav.reset();
                     }
                 }
                 SceneEvent::SpawnDebris(pos) =>{
-                    self.spawn_debris(*pos, config, asset_db);
+                    self.particles.burst(&self.explosion_effect, *pos, Vec2::unit_x(), Vec2::zero());
+                }
+                SceneEvent::EmitParticles { effect, count, pos, dir, velocity } => {
+                    self.particles.emit(effect, *count, *pos, *dir, *velocity);
                 }
                 SceneEvent::Shoot(layer) =>{
                     if (*layer == ColLayer::BulletPlayer){
@@ -292,37 +551,83 @@ impl Scene for GameScene
         }
 
         if (self.asteroids_ids.len() == 0){
-            self.asteroids_count += 2;
+            // the versus arena keeps the same amount of hazards
+            if self.rounds.is_none() {
+                self.asteroids_count += 2;
+            }
+            self.set_level(self.level + 1, config);
+            self.rejoin_players(config);
             self.spawn_asteroids(&config)
         }
     }
 
+    fn update(&mut self, dt: f32, input: &Input, config: &Config, assets_db: &AssetsDB) -> SceneSwitch {
+        if self.start_round {
+            self.start_round = false;
+            self.next_round(config);
+        }
+
+        if self.lockstep && let GameState::Results = self.state {
+            self.results_timer -= dt;
+            if self.results_timer <= 0.0 {
+                self.next_round(config);
+            }
+        }
+
+        if self.demo && let GameState::End = self.state {
+            self.start_game(config);
+        }
+
+        // online the input belongs to the simulation, the local UI is driven by the mouse
+        match self.state {
+            _ if self.lockstep || self.demo => {}
+            GameState::End | GameState::Results => self.nav.update(input),
+            GameState::Active if input.is_action_pressed(Action::Pause) => return SceneSwitch::Push(SceneId::Pause),
+            GameState::Active => {}
+        }
+        let frozen = matches!(self.state, GameState::Results);
+        if !frozen {
+            self.update_entity(dt, input, config, assets_db);
+        }
+        SceneSwitch::None
+    }
+
+    fn collision_rules(&self) -> CollisionRules {
+        match self.mode {
+            GameMode::Versus { .. } => CollisionRules::versus(),
+            _ => CollisionRules::default(),
+        }
+    }
+
+    fn hash_state(&self, hasher: &mut DefaultHasher) {
+        for player in &self.players {
+            (player.entity_id.is_some(), player.lives, player.score).hash(hasher);
+        }
+        if let Some(rounds) = &self.rounds {
+            for index in 0..self.players.len() {
+                (rounds.get_kills(index), rounds.get_deaths(index), rounds.get_wins(index)).hash(hasher);
+            }
+        }
+        (self.level, self.asteroids_ids.len(), self.enemy_timer.to_bits()).hash(hasher);
+    }
+
+    fn render(&mut self, screen: &mut Screen) {
+        self.background.draw(screen);
+        self.render_layer(screen, RenderLayer::Background);
+        self.render_layer(screen, RenderLayer::World);
+        self.particles.draw(screen);
+        self.render_layer(screen, RenderLayer::Foreground);
+
+        if let GameState::Active = self.state {
+            self.draw_hud(screen);
+        }
+    }
+
     fn ui(&mut self, ctx: &Context) -> SceneSwitch {
         match self.state {
-            GameState::Active => {
-                let frame = ui_transparent_frame();
-                egui::TopBottomPanel::top("bottom_data")
-                    .frame(frame)
-                    .show_separator_line(false)
-                    .min_height(35.0)
-                    .show(ctx, |ui| {
-                        ui.with_layout(Layout::left_to_right(Align::Center), |ui| {
-                            ui.label(
-                                egui::RichText::new(format!("SCORES: {}", self.scores))
-                                    .size(20.0)
-                                    .strong(),
-                            );
-
-                            let space = ui.available_width();
-                            ui.add_space(space - (30.0 * self.player_healths as f32));
-
-                            ui.horizontal(|ui| {
-                                for _ in 0..self.player_healths {
-                                    ui_draw_icon(ui, &self.health_icon, Vec2::new(20.0, 20.0));
-                                }
-                            });
-                        });
-                    });
+            GameState::Active => {}
+            GameState::Results => {
+                return self.draw_results(ctx);
             }
             GameState::End => {
                 let can_send = self.web_client.is_available_name();
@@ -331,6 +636,11 @@ impl Scene for GameScene
                 let frame = ui_transparent_frame();
                 let mut scene_switch = SceneSwitch::None;
 
+                self.nav.begin(ctx);
+                if self.nav.take_back() {
+                    scene_switch = SceneSwitch::Switch(SceneId::Menu);
+                }
+
                 egui::CentralPanel::default().frame(frame).show(ctx, |ui| {
                     let (avail, title_rect) = ui_title_rect(ui);
                     let (card_rect, card_frame) = ui_get_card_rect(avail);
@@ -339,22 +649,32 @@ impl Scene for GameScene
                         card_frame.show(ui, |ui| {
                             ui.vertical_centered(|ui| {
                                 ui.label(
-                                    egui::RichText::new(format!("SCORES\n{:06}", self.scores))
+                                    egui::RichText::new(format!("SCORES\n{:06}", self.total_score()))
                                         .size(64.0)
                                         .strong(),
                                 );
+                                if self.players.len() > 1 {
+                                    for (index, player) in self.players.iter().enumerate() {
+                                        let [_, r, g, b] = PLAYER_COLORS[index].to_be_bytes();
+                                        ui.label(
+                                            egui::RichText::new(format!("P{}  {:06}", index + 1, player.score))
+                                                .size(24.0)
+                                                .color(egui::Color32::from_rgb(r, g, b)),
+                                        );
+                                    }
+                                }
 
                                 ui.add_space(18.0);
 
                                 ui.label(egui::RichText::new("Enter your nickname").size(20.0));
                                 ui.add_space(8.0);
 
-                                let resp = ui.add_sized(
+                                let resp = self.nav.text_edit(ui, |ui| ui.add_sized(
                                     [320.0, 40.0],
                                     egui::TextEdit::singleline(&mut nickname)
                                         .hint_text("Name...")
                                         .font(egui::FontId::proportional(26.0)),
-                                );
+                                ));
 
                                 if (resp.changed()) {
                                     self.web_client.change_nickname(nickname);
@@ -363,14 +683,14 @@ impl Scene for GameScene
 
 
                                 ui.add_enabled_ui(can_send, |ui| {
-                                    if ui_button(ui, "Send")
+                                    if self.nav.button(ui, "Send")
                                     {
-                                        self.web_client.send_web_data(self.scores);
+                                        self.web_client.send_web_data(self.total_score());
                                         scene_switch = SceneSwitch::Switch(SceneId::Menu);
                                     }
                                 });
                                 ui.add_space(10.0);
-                                if ui_button(ui, "Menu")
+                                if self.nav.button(ui, "Menu")
                                 {
                                     scene_switch = SceneSwitch::Switch(SceneId::Menu);
                                 }
@@ -378,6 +698,7 @@ impl Scene for GameScene
                         });
                     });
                 });
+                self.nav.end();
 
 
                 return scene_switch;
@@ -1,34 +1,190 @@
 ﻿use crate::assetsdb_lib::c_assets_db::AssetsDB;
+use crate::classes::c_attract_demo::AttractDemo;
+use crate::classes::c_input::{Input, MAX_PLAYERS};
+use crate::classes::c_input_bindings::{key_name, InputBindings};
+use crate::classes::c_menu_nav::MenuNav;
+use crate::classes::c_round_manager::DEFAULT_KILL_LIMIT;
+use crate::classes::e_action::Action;
+use crate::classes::e_game_mode::GameMode;
 use crate::classes::t_entity::Entity;
-use crate::config_lib::c_config::Config;
+use crate::config_lib::c_config::{Config, KEYBOARD_PLAYERS};
+use crate::net_lib::c_lobby::Lobby;
+use crate::net_lib::c_lockstep::LockstepSession;
+use crate::render_lib::c_transition::Transition;
 use crate::render_lib::t_screen_data::Screen;
 use crate::scenes_lib::e_scene_switch::SceneSwitch;
 use crate::scenes_lib::e_sceneid::SceneId;
 use crate::scenes_lib::t_scene::Scene;
 use egui::{Context, Frame, Rect, Ui};
-use crate::render_lib::f_drawers::{ui_button, ui_get_card_rect, ui_header, ui_title_rect, ui_transparent_frame};
+use vek::Vec2;
+use winit::keyboard::KeyCode;
+use crate::render_lib::f_drawers::{ui_get_card_rect, ui_header, ui_title_rect, ui_transparent_frame};
 use crate::web_lib::c_web_client::{LeaderboardState, WebClient};
 
-#[derive(Default)]
+#[derive(Default, PartialEq)]
 enum MenuTab{
     #[default]
     MainScreen,
     CreditsScreen,
-    LeaderboardScreen
+    LeaderboardScreen,
+    ControlsScreen,
+    VersusScreen,
+    OnlineScreen
+}
+
+/// Pages the attract loop cycles through while nobody touches the main menu.
+#[derive(Clone, Copy, PartialEq)]
+enum AttractPage{
+    Demo,
+    Scores,
+    Title,
+}
+
+impl AttractPage {
+    fn next(self) -> AttractPage {
+        match self {
+            AttractPage::Demo => AttractPage::Scores,
+            AttractPage::Scores => AttractPage::Title,
+            AttractPage::Title => AttractPage::Demo,
+        }
+    }
 }
 
+/// Idle seconds on the main screen before the attract loop starts, and seconds per page.
+const ATTRACT_IDLE: f32 = 20.0;
+const ATTRACT_PAGE_TIME: f32 = 12.0;
+
+/// Binding slots shown per action on the controls screen.
+const BINDING_SLOTS: usize = 2;
+const DEFAULT_PORT: u16 = 7777;
+
 #[derive(Default)]
 pub struct MenuScene{
     entities: Vec<Box<dyn Entity>>,
 
     web_client: WebClient,
     tab: MenuTab,
-    action: SceneSwitch
+    action: SceneSwitch,
+    nav: MenuNav,
+
+    /// Keyboard bindings of every local player, `bindings_player` is the one shown.
+    bindings: Vec<InputBindings>,
+    bindings_player: usize,
+    /// Action and slot waiting for a key press.
+    rebinding: Option<(Action, usize)>,
+    bindings_changed: bool,
+    /// Opened from the pause menu: only the controls screen, back returns to the pause menu.
+    settings: bool,
+
+    /// Leading players that have keyboard keys or a connected controller, for the versus screen.
+    controllable_players: usize,
+
+    /// Online screen: address to join, port to host on and the mode a host offers.
+    join_address: String,
+    host_port: String,
+    online_versus: bool,
+    playfield: Vec2<usize>,
+    lobby: Option<Lobby>,
+    /// Connected session, handed to the game on the next frame.
+    session: Option<LockstepSession>,
+    /// Why hosting or joining could not start.
+    online_error: Option<String>,
+
+    /// AI game running behind the menu, `None` for the settings overlay.
+    demo: Option<AttractDemo>,
+    idle: f32,
+    /// Current attract page and the seconds it has been shown.
+    attract: Option<(AttractPage, f32)>,
+    /// Input arrived during the attract loop, the menu comes back after this frame's ui.
+    waking: bool,
 }
 
 impl Scene for MenuScene
 {
     fn create_scene(&mut self, config: &Config, screen: &Screen, assets_db: &AssetsDB) {
+        self.bindings = (0..KEYBOARD_PLAYERS)
+            .map(|p| config.bindings(p).cloned().unwrap_or_else(|| InputBindings::for_player(p)))
+            .collect();
+        self.join_address = format!("127.0.0.1:{DEFAULT_PORT}");
+        self.host_port = DEFAULT_PORT.to_string();
+        self.playfield = config.size();
+
+        if !self.settings {
+            self.demo = Some(AttractDemo::new(config, screen, assets_db));
+        }
+    }
+
+    fn update(&mut self, dt: f32, input: &Input, config: &Config, assets_db: &AssetsDB) -> SceneSwitch {
+        self.update_entity(dt, input, config, assets_db);
+        self.controllable_players = (0..MAX_PLAYERS).take_while(|p| input.is_player_controllable(*p)).count();
+
+        if let Some(demo) = &mut self.demo {
+            demo.update(dt, config, assets_db);
+        }
+        self.update_attract(dt, input);
+        if self.attract.is_some() {
+            return SceneSwitch::None;
+        }
+
+        // while a binding is captured every key belongs to it
+        if self.rebinding.is_none() {
+            self.nav.update(input);
+        }
+
+        if let Some((action, slot)) = self.rebinding
+            && let Some(key) = input.get_pressed_keys().first().copied() {
+            match key {
+                KeyCode::Escape => {}
+                KeyCode::Delete | KeyCode::Backspace => {
+                    self.bindings[self.bindings_player].unbind(action, slot);
+                    self.bindings_changed = true;
+                }
+                _ => {
+                    // players share the keyboard, a key flies one ship only
+                    for (player, bindings) in self.bindings.iter_mut().enumerate() {
+                        if player == self.bindings_player {
+                            bindings.bind(action, slot, key);
+                        } else {
+                            bindings.remove_key(key, action.is_menu());
+                        }
+                    }
+                    self.bindings_changed = true;
+                }
+            }
+            self.rebinding = None;
+        }
+
+        if let Some(lobby) = &mut self.lobby {
+            lobby.poll(dt);
+            self.session = lobby.take_session();
+            if self.session.is_some() {
+                self.lobby = None;
+            }
+        }
+
+        SceneSwitch::None
+    }
+
+    fn render(&mut self, screen: &mut Screen) {
+        if let Some(demo) = &mut self.demo {
+            demo.render(screen);
+        }
+    }
+
+    fn take_lockstep(&mut self) -> Option<LockstepSession> {
+        self.session.take()
+    }
+
+    fn draws_below(&self) -> bool {
+        self.settings
+    }
+
+    fn take_bindings(&mut self) -> Option<Vec<InputBindings>> {
+        if !self.bindings_changed {
+            return None;
+        }
+        self.bindings_changed = false;
+        Some(self.bindings.clone())
     }
     
     fn get_scene_name(&self) -> String{
@@ -48,6 +204,22 @@ impl Scene for MenuScene
 
         self.action = SceneSwitch::None;
 
+        if let Some((page, _)) = self.attract {
+            self.draw_attract_page(ctx, page);
+            // the waking press landed on the attract page, not on a menu button
+            if self.waking {
+                self.attract = None;
+                self.waking = false;
+                self.nav.reset();
+            }
+            return SceneSwitch::None;
+        }
+
+        self.nav.begin(ctx);
+        if self.nav.take_back() && self.tab != MenuTab::MainScreen {
+            self.back();
+        }
+
         match self.tab {
             MenuTab::MainScreen => {
                 self.draw_main_screen(ctx);
@@ -58,7 +230,17 @@ impl Scene for MenuScene
             MenuTab::LeaderboardScreen => {
                 self.draw_leaderboard_screen(ctx);
             }
+            MenuTab::ControlsScreen => {
+                self.draw_controls_screen(ctx);
+            }
+            MenuTab::VersusScreen => {
+                self.draw_versus_screen(ctx);
+            }
+            MenuTab::OnlineScreen => {
+                self.draw_online_screen(ctx);
+            }
         }
+        self.nav.end();
 
 
 
@@ -68,6 +250,54 @@ impl Scene for MenuScene
 }
 
 impl MenuScene {
+    pub fn settings() -> Self {
+        Self { settings: true, tab: MenuTab::ControlsScreen, ..Default::default() }
+    }
+
+    fn back(&mut self) {
+        if self.settings {
+            self.action = SceneSwitch::Replace(SceneId::Pause);
+        } else {
+            self.open_tab(MenuTab::MainScreen);
+        }
+    }
+
+    /// Starts the attract loop after `ATTRACT_IDLE` seconds without input on the main screen,
+    /// then cycles demo, high scores and title until any input ends it.
+    fn update_attract(&mut self, dt: f32, input: &Input) {
+        if input.has_activity() {
+            self.idle = 0.0;
+            self.waking = self.attract.is_some();
+            return;
+        }
+
+        if let Some((page, time)) = &mut self.attract {
+            *time += dt;
+            if *time >= ATTRACT_PAGE_TIME {
+                *page = page.next();
+                *time = 0.0;
+                if *page == AttractPage::Scores {
+                    self.web_client.get_leaderboard_data();
+                }
+            }
+            return;
+        }
+
+        let waiting = self.demo.is_some() && self.tab == MenuTab::MainScreen && self.lobby.is_none();
+        self.idle = if waiting { self.idle + dt } else { 0.0 };
+        if self.idle >= ATTRACT_IDLE {
+            self.attract = Some((AttractPage::Demo, 0.0));
+        }
+    }
+
+    fn open_tab(&mut self, tab: MenuTab) {
+        self.tab = tab;
+        self.rebinding = None;
+        self.lobby = None;
+        self.online_error = None;
+        self.nav.reset();
+    }
+
     fn draw_main_screen(&mut self, ctx: &Context) {
         let frame = ui_transparent_frame();
 
@@ -89,21 +319,36 @@ impl MenuScene {
 
                             ui.vertical_centered(|ui| {
 
-                                for label in ["Play", "Leaderboard", "Credits", "Exit"] {
-                                    if ui_button(ui, label) {
+                                for label in ["Play", "Co-op", "Versus", "Online", "Leaderboard", "Controls", "Editor", "Credits", "Exit"] {
+                                    if self.nav.button(ui, label) {
                                         match label {
                                             "Exit" => {
                                                 self.action = SceneSwitch::Quit;
                                             }
                                             "Play" => {
-                                                self.action = SceneSwitch::Switch(SceneId::Game);
+                                                self.action = SceneSwitch::SwitchWith(SceneId::Game, Transition::Wipe);
+                                            },
+                                            "Co-op" => {
+                                                self.action = SceneSwitch::SwitchWith(SceneId::Coop, Transition::Wipe);
+                                            },
+                                            "Versus" => {
+                                                self.open_tab(MenuTab::VersusScreen);
+                                            },
+                                            "Online" => {
+                                                self.open_tab(MenuTab::OnlineScreen);
                                             },
                                             "Leaderboard" => {
                                                 self.web_client.get_leaderboard_data();
-                                                self.tab = MenuTab::LeaderboardScreen;
+                                                self.open_tab(MenuTab::LeaderboardScreen);
+                                            }
+                                            "Controls" => {
+                                                self.open_tab(MenuTab::ControlsScreen);
+                                            }
+                                            "Editor" => {
+                                                self.action = SceneSwitch::Switch(SceneId::Editor);
                                             }
                                             "Credits" => {
-                                                self.tab = MenuTab::CreditsScreen;
+                                                self.open_tab(MenuTab::CreditsScreen);
                                             }
                                             _ => {}
                                         };
@@ -138,69 +383,196 @@ impl MenuScene {
                 ui.allocate_ui_at_rect(card_rect, |ui| {
                     card_frame.show(ui, |ui| {
 
-                        ui.horizontal(|ui| {
-                            ui.label(egui::RichText::new("#").size(18.0).strong());
-                            ui.add_space(12.0);
-                            ui.label(egui::RichText::new("NAME").size(18.0).strong());
-                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                                ui.label(egui::RichText::new("SCORE").size(18.0).strong());
+                        self.leaderboard_table(ui);
+
+                        ui.add_space(12.0);
+                        ui.separator();
+                        ui.add_space(12.0);
+
+                        ui.horizontal_centered(|ui| {
+                            if self.nav.button(ui, "Refresh") {
+                                self.web_client.get_leaderboard_data();
+                            }
+                            ui.add_space(10.0);
+                            if self.nav.button(ui, "Back") {
+                                self.open_tab(MenuTab::MainScreen);
+                            }
+                        });
+                    });
+                });
+            });
+    }
+
+
+    fn leaderboard_table(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new("#").size(18.0).strong());
+            ui.add_space(12.0);
+            ui.label(egui::RichText::new("NAME").size(18.0).strong());
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(egui::RichText::new("SCORE").size(18.0).strong());
+            });
+        });
+
+        ui.add_space(8.0);
+        ui.separator();
+        ui.add_space(8.0);
+
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                let leaderboard_data = self.web_client.get_leaderboard();
+
+                match leaderboard_data {
+                    LeaderboardState::Idle => {}
+                    LeaderboardState::Loading => {
+                        ui.label("loading...");
+                        self.web_client.poll_network();
+                    }
+                    LeaderboardState::Ready(list) => {
+                        for (i, e) in list.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                ui.label(format!("{:>2}", i + 1));
+                                ui.add_space(12.0);
+
+                                ui.label(&e.name);
+
+                                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                    ui.label(format!("{}", e.score));
+                                });
                             });
+                            ui.add_space(4.0);
+                        }
+
+                        if list.is_empty() {
+                            ui.add_space(10.0);
+                            ui.label("No data. Press Refresh.");
+                        }
+                    }
+                    LeaderboardState::Error(error) => {
+                        ui.label(error.to_string());
+                    }
+                }
+
+
+            });
+    }
+
+    /// Full-screen pages of the attract loop, drawn over the demo instead of the menu.
+    fn draw_attract_page(&mut self, ctx: &Context, page: AttractPage) {
+        let frame_bg = ui_transparent_frame();
+
+        egui::CentralPanel::default()
+            .frame(frame_bg)
+            .show(ctx, |ui| {
+                match page {
+                    AttractPage::Demo => {
+                        ui.with_layout(egui::Layout::bottom_up(egui::Align::Center), |ui| {
+                            ui.add_space(24.0);
+                            ui.label(egui::RichText::new("DEMO - PRESS ANY KEY").size(20.0).strong());
                         });
+                    }
+                    AttractPage::Scores => {
+                        let (avail, title_rect) = ui_title_rect(ui);
 
-                        ui.add_space(8.0);
-                        ui.separator();
-                        ui.add_space(8.0);
+                        ui.allocate_ui_at_rect(title_rect, |ui| {
+                            ui_header(ui, "HIGH SCORES");
+                        });
 
-                        egui::ScrollArea::vertical()
-                            .auto_shrink([false, false])
-                            .show(ui, |ui| {
-                                let leaderboard_data = self.web_client.get_leaderboard();
+                        let (card_rect, card_frame) = ui_get_card_rect(avail);
 
-                                match leaderboard_data {
-                                    LeaderboardState::Idle => {}
-                                    LeaderboardState::Loading => {
-                                        ui.label("loading...");
-                                        self.web_client.poll_network();
-                                    }
-                                    LeaderboardState::Ready(list) => {
-                                        for (i, e) in list.iter().enumerate() {
-                                            ui.horizontal(|ui| {
-                                                ui.label(format!("{:>2}", i + 1));
-                                                ui.add_space(12.0);
-
-                                                ui.label(&e.name);
-
-                                                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                                                    ui.label(format!("{}", e.score));
-                                                });
-                                            });
-                                            ui.add_space(4.0);
-                                        }
+                        ui.allocate_ui_at_rect(card_rect, |ui| {
+                            card_frame.show(ui, |ui| {
+                                self.leaderboard_table(ui);
+                            });
+                        });
+                    }
+                    AttractPage::Title => {
+                        ui.vertical_centered(|ui| {
+                            ui_header(ui, "ASTEROIDS");
+                            ui.add_space(ui.available_height() * 0.4);
+                            ui.label(egui::RichText::new("PRESS ANY KEY").size(28.0).strong());
+                        });
+                    }
+                }
+            });
+    }
 
-                                        if list.is_empty() {
-                                            ui.add_space(10.0);
-                                            ui.label("No data. Press Refresh.");
-                                        }
-                                    }
-                                    LeaderboardState::Error(error) => {
-                                        ui.label(format!("{}", error));
-                                    }
-                                }
 
+    pub fn draw_controls_screen(&mut self, ctx: &Context) {
+        let frame_bg = ui_transparent_frame();
+
+        egui::CentralPanel::default()
+            .frame(frame_bg)
+            .show(ctx, |ui| {
+                let (avail, title_rect) = ui_title_rect(ui);
+
+                ui.allocate_ui_at_rect(title_rect, |ui| {
+                    ui_header(ui, "CONTROLS");
+                });
+
+                let (card_rect, card_frame) = ui_get_card_rect(avail);
+
+                ui.allocate_ui_at_rect(card_rect, |ui| {
+                    card_frame.show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            for player in 0..KEYBOARD_PLAYERS {
+                                let label = format!("Player {}", player + 1);
+                                let selected = self.bindings_player == player;
+                                if self.nav.widget(ui, |ui| ui.selectable_label(selected, egui::RichText::new(label).size(18.0))) {
+                                    self.bindings_player = player;
+                                    self.rebinding = None;
+                                }
+                                ui.add_space(10.0);
+                            }
+                        });
+                        ui.add_space(8.0);
 
+                        egui::ScrollArea::vertical()
+                            .max_height(ui.available_height() - 110.0)
+                            .show(ui, |ui| {
+                                egui::Grid::new("bindings")
+                                    .num_columns(BINDING_SLOTS + 1)
+                                    .spacing([16.0, 8.0])
+                                    .show(ui, |ui| {
+                                        // only the first player drives the menus
+                                        let actions = Action::ALL.into_iter()
+                                            .filter(|a| self.bindings_player == 0 || !(a.is_menu() || *a == Action::Pause));
+                                        for action in actions {
+                                            ui.label(egui::RichText::new(action.label()).size(18.0));
+
+                                            for slot in 0..BINDING_SLOTS {
+                                                let text = if self.rebinding == Some((action, slot)) {
+                                                    "press a key...".to_string()
+                                                } else {
+                                                    self.bindings[self.bindings_player].keys(action).get(slot).map(|k| key_name(*k)).unwrap_or("-".to_string())
+                                                };
+
+                                                if self.nav.widget(ui, |ui| ui.add_sized([140.0, 26.0], egui::Button::new(text))) {
+                                                    self.rebinding = Some((action, slot));
+                                                }
+                                            }
+                                            ui.end_row();
+                                        }
+                                    });
                             });
 
+                        ui.add_space(8.0);
+                        ui.label("Select a slot and press a key. Delete clears the slot, Escape cancels.");
+
                         ui.add_space(12.0);
                         ui.separator();
                         ui.add_space(12.0);
 
                         ui.horizontal_centered(|ui| {
-                            if ui_button(ui, "Refresh") {
-                                self.web_client.get_leaderboard_data();
+                            if self.nav.button(ui, "Defaults") {
+                                self.bindings[self.bindings_player] = InputBindings::for_player(self.bindings_player);
+                                self.bindings_changed = true;
+                                self.rebinding = None;
                             }
                             ui.add_space(10.0);
-                            if ui_button(ui, "Back") {
-                                self.action = SceneSwitch::Switch(SceneId::Menu);
+                            if self.nav.button(ui, "Back") {
+                                self.back();
                             }
                         });
                     });
@@ -209,6 +581,141 @@ impl MenuScene {
     }
 
 
+    pub fn draw_versus_screen(&mut self, ctx: &Context) {
+        let frame_bg = ui_transparent_frame();
+
+        egui::CentralPanel::default()
+            .frame(frame_bg)
+            .show(ctx, |ui| {
+                let (avail, title_rect) = ui_title_rect(ui);
+
+                ui.allocate_ui_at_rect(title_rect, |ui| {
+                    ui_header(ui, "VERSUS");
+                });
+
+                let (card_rect, card_frame) = ui_get_card_rect(avail);
+
+                ui.allocate_ui_at_rect(card_rect, |ui| {
+                    card_frame.show(ui, |ui| {
+                        ui.vertical_centered(|ui| {
+                            ui.label(format!("First to {} kills wins the round. Asteroids are hazards, crashing costs a kill.", DEFAULT_KILL_LIMIT));
+                            ui.label("Players 1 and 2 share the keyboard. Controllers go to the players in connection order, so players 3 and 4 need the third and fourth controller.");
+                            ui.add_space(12.0);
+
+                            for players in 2..=MAX_PLAYERS {
+                                ui.add_enabled_ui(players <= self.controllable_players.max(2), |ui| {
+                                    if self.nav.button(ui, &format!("{} players", players)) {
+                                        self.action = SceneSwitch::SwitchWith(SceneId::Versus(players), Transition::Wipe);
+                                    }
+                                });
+                                ui.add_space(8.0);
+                            }
+
+                            ui.add_space(12.0);
+                            if self.nav.button(ui, "Back") {
+                                self.open_tab(MenuTab::MainScreen);
+                            }
+                        });
+                    });
+                });
+            });
+    }
+
+
+    /// Two players over the network: one hosts on a port, the other joins by address.
+    pub fn draw_online_screen(&mut self, ctx: &Context) {
+        let frame_bg = ui_transparent_frame();
+
+        egui::CentralPanel::default()
+            .frame(frame_bg)
+            .show(ctx, |ui| {
+                let (avail, title_rect) = ui_title_rect(ui);
+
+                ui.allocate_ui_at_rect(title_rect, |ui| {
+                    ui_header(ui, "ONLINE");
+                });
+
+                let (card_rect, card_frame) = ui_get_card_rect(avail);
+
+                ui.allocate_ui_at_rect(card_rect, |ui| {
+                    card_frame.show(ui, |ui| {
+                        ui.vertical_centered(|ui| {
+                            ui.label("Both players need the same version of the game and the same window size.");
+                            ui.add_space(12.0);
+
+                            ui.add_enabled_ui(self.lobby.is_none(), |ui| {
+                                ui.horizontal(|ui| {
+                                    ui.label("Mode");
+                                    if self.nav.widget(ui, |ui| ui.selectable_label(!self.online_versus, "Co-op")) {
+                                        self.online_versus = false;
+                                    }
+                                    if self.nav.widget(ui, |ui| ui.selectable_label(self.online_versus, "Versus")) {
+                                        self.online_versus = true;
+                                    }
+                                });
+                                ui.horizontal(|ui| {
+                                    ui.label("Port");
+                                    self.nav.text_edit(ui, |ui| ui.add_sized([120.0, 28.0], egui::TextEdit::singleline(&mut self.host_port)));
+                                    if self.nav.button(ui, "Host") {
+                                        self.start_host();
+                                    }
+                                });
+                                ui.add_space(8.0);
+                                ui.horizontal(|ui| {
+                                    ui.label("Address");
+                                    self.nav.text_edit(ui, |ui| ui.add_sized([220.0, 28.0], egui::TextEdit::singleline(&mut self.join_address)));
+                                    if self.nav.button(ui, "Join") {
+                                        self.start_join();
+                                    }
+                                });
+                            });
+
+                            ui.add_space(12.0);
+                            let status = match (&self.lobby, &self.online_error) {
+                                (_, Some(error)) => error.clone(),
+                                (Some(lobby), None) => lobby.describe(),
+                                (None, None) => String::new(),
+                            };
+                            ui.label(status);
+
+                            if self.lobby.is_some() && self.nav.button(ui, "Cancel") {
+                                self.lobby = None;
+                            }
+
+                            ui.add_space(12.0);
+                            if self.nav.button(ui, "Back") {
+                                self.open_tab(MenuTab::MainScreen);
+                            }
+                        });
+                    });
+                });
+            });
+    }
+
+    fn start_host(&mut self) {
+        let mode = if self.online_versus { GameMode::Versus { players: 2 } } else { GameMode::Coop };
+        let lobby = self.host_port.trim().parse::<u16>()
+            .map_err(|_| format!("{} is not a port", self.host_port.trim()))
+            .and_then(|port| Lobby::host(port, mode, self.playfield).map_err(|e| format!("Cannot host: {e}")));
+        self.set_lobby(lobby);
+    }
+
+    fn start_join(&mut self) {
+        let lobby = Lobby::join(self.join_address.trim(), self.playfield)
+            .map_err(|e| format!("Cannot join {}: {e}", self.join_address.trim()));
+        self.set_lobby(lobby);
+    }
+
+    fn set_lobby(&mut self, lobby: Result<Lobby, String>) {
+        match lobby {
+            Ok(lobby) => {
+                self.lobby = Some(lobby);
+                self.online_error = None;
+            }
+            Err(error) => self.online_error = Some(error),
+        }
+    }
+
     pub fn draw_credits_screen(&mut self, ctx: &Context) {
         let frame_bg = ui_transparent_frame();
 
@@ -231,8 +738,8 @@ impl MenuScene {
                         ui.label("Repo: https://github.com/YagirProtect/Rust-Asteroids");
 
                         ui.horizontal_centered(|ui| {
-                            if ui_button(ui, "Back") {
-                                self.action = SceneSwitch::Switch(SceneId::Menu);
+                            if self.nav.button(ui, "Back") {
+                                self.open_tab(MenuTab::MainScreen);
                             }
                         });
                     });
@@ -0,0 +1,95 @@
+﻿use egui::Context;
+use crate::assetsdb_lib::c_assets_db::AssetsDB;
+use crate::classes::c_input::Input;
+use crate::classes::c_menu_nav::MenuNav;
+use crate::classes::e_action::Action;
+use crate::classes::t_entity::Entity;
+use crate::config_lib::c_config::Config;
+use crate::render_lib::f_drawers::{ui_get_card_rect, ui_header, ui_title_rect};
+use crate::render_lib::c_transition::Transition;
+use crate::render_lib::t_screen_data::Screen;
+use crate::scenes_lib::e_scene_switch::SceneSwitch;
+use crate::scenes_lib::e_sceneid::SceneId;
+use crate::scenes_lib::t_scene::Scene;
+
+/// Pushed over a running game: the game below stays on screen but is frozen until resumed.
+#[derive(Default)]
+pub struct PauseScene {
+    entities: Vec<Box<dyn Entity>>,
+    nav: MenuNav,
+    action: SceneSwitch,
+}
+
+impl Scene for PauseScene {
+    fn create_scene(&mut self, _config: &Config, _screen: &Screen, _assets_db: &AssetsDB) {
+        self.nav.reset();
+    }
+
+    fn get_scene_name(&self) -> String {
+        String::from("PauseScene")
+    }
+
+    fn update(&mut self, _dt: f32, input: &Input, _config: &Config, _assets_db: &AssetsDB) -> SceneSwitch {
+        self.nav.update(input);
+
+        if input.is_action_pressed(Action::Pause) {
+            return SceneSwitch::Pop;
+        }
+        SceneSwitch::None
+    }
+
+    fn draws_below(&self) -> bool {
+        true
+    }
+
+    fn ui(&mut self, ctx: &Context) -> SceneSwitch {
+        self.action = SceneSwitch::None;
+
+        self.nav.begin(ctx);
+        if self.nav.take_back() {
+            self.action = SceneSwitch::Pop;
+        }
+
+        // dims the frozen game instead of hiding it
+        let frame = egui::Frame::none().fill(egui::Color32::from_black_alpha(150));
+
+        egui::CentralPanel::default().frame(frame).show(ctx, |ui| {
+            let (avail, title_rect) = ui_title_rect(ui);
+
+            ui.allocate_ui_at_rect(title_rect, |ui| {
+                ui_header(ui, "PAUSED");
+            });
+
+            let (card_rect, card_frame) = ui_get_card_rect(avail);
+
+            ui.allocate_ui_at_rect(card_rect, |ui| {
+                card_frame.show(ui, |ui| {
+                    ui.vertical_centered(|ui| {
+                        for label in ["Resume", "Restart", "Settings", "Quit to menu"] {
+                            if self.nav.button(ui, label) {
+                                self.action = match label {
+                                    "Resume" => SceneSwitch::Pop,
+                                    "Restart" => SceneSwitch::Restart,
+                                    "Settings" => SceneSwitch::Replace(SceneId::Settings),
+                                    _ => SceneSwitch::SwitchWith(SceneId::Menu, Transition::Collapse),
+                                };
+                            }
+                            ui.add_space(8.0);
+                        }
+                    });
+                });
+            });
+        });
+        self.nav.end();
+
+        self.action
+    }
+
+    fn get_entities(&self) -> &Vec<Box<dyn Entity>> {
+        &self.entities
+    }
+
+    fn get_entities_mut(&mut self) -> &mut Vec<Box<dyn Entity>> {
+        &mut self.entities
+    }
+}
@@ -14,7 +14,7 @@ use rand::seq::IteratorRandom;
 use vek::Vec2;
 use crate::classes::c_enemy_entity::EnemyEntity;
 use crate::mesh_lib::c_mesh::Mesh;
-use crate::render_lib::f_drawers::{ui_button, ui_draw_icon, ui_get_card_rect, ui_title_rect, ui_transparent_frame};
+use crate::render_lib::f_drawers::{ui_get_card_rect, ui_title_rect, ui_transparent_frame};
 use crate::scenes_lib::e_scene_event::SceneEvent;
 use crate::scenes_lib::e_scene_switch::SceneSwitch;
 use crate::scenes_lib::e_sceneid::SceneId;
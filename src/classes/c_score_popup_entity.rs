@@ -0,0 +1,91 @@
+﻿use std::rc::Rc;
+use vek::Vec2;
+use crate::assetsdb_lib::c_assets_db::AssetsDB;
+use crate::classes::c_input::Input;
+use crate::classes::t_entity::Entity;
+use crate::collisions_lib::e_col_layers::ColLayer;
+use crate::collisions_lib::t_collision::Collide;
+use crate::config_lib::c_config::Config;
+use crate::font_lib::c_stroke_font::StrokeFont;
+use crate::render_lib::e_render_layer::RenderLayer;
+use crate::render_lib::f_drawers::draw_text;
+use crate::render_lib::t_drawable::Drawable;
+use crate::render_lib::t_screen_data::Screen;
+use crate::scenes_lib::e_scene_event::SceneEvent;
+use crate::scenes_lib::e_scene_event::SceneEvent::DestroyEntity;
+
+/// Floating "+N" text that drifts up and fades out where points were earned.
+pub struct ScorePopupEntity {
+    id: u32,
+    position: Vec2<f32>,
+    font: Rc<StrokeFont>,
+    text: String,
+
+    timer: f32,
+    life_time: f32,
+}
+
+impl ScorePopupEntity {
+    pub fn new(position: Vec2<f32>, font: Rc<StrokeFont>, text: String) -> ScorePopupEntity {
+        ScorePopupEntity {
+            id: 0,
+            position,
+            font,
+            text,
+            timer: 0.0,
+            life_time: 0.8,
+        }
+    }
+}
+
+impl Drawable for ScorePopupEntity {
+    fn get_render_layer(&self) -> RenderLayer {
+        RenderLayer::Foreground
+    }
+
+    fn draw(&mut self, screen: &mut Screen) {
+        let scale = 3.0;
+        let size = self.font.measure(&self.text, scale);
+
+        let fade = (1.0 - self.timer / self.life_time).clamp(0.0, 1.0);
+        let c = (255.0 * fade) as u32;
+        let color = (c << 16) | (c << 8) | c;
+
+        draw_text(screen, &self.font, &self.text, self.position - size / 2.0, scale, 0.0, color);
+    }
+}
+
+impl Collide for ScorePopupEntity {
+    fn can_collide(&self) -> bool {
+        false
+    }
+    fn get_collision_layer(&self) -> ColLayer {
+        ColLayer::Player
+    }
+}
+
+impl Entity for ScorePopupEntity {
+    fn set_entity_id(&mut self, entity_id: u32) {
+        self.id = entity_id;
+    }
+
+    fn get_entity_id(&self) -> u32 {
+        self.id
+    }
+
+    fn update(&mut self, delta_time: f32, _input: &Input, _config: &Config, _assets_db: &AssetsDB) -> Vec<SceneEvent> {
+        self.position.y -= 40.0 * delta_time;
+        self.timer += delta_time;
+
+        if self.timer > self.life_time {
+            return vec![
+                DestroyEntity(self.id),
+            ]
+        }
+        vec![]
+    }
+
+    fn get_position(&self) -> &Vec2<f32> {
+        &self.position
+    }
+}
@@ -0,0 +1,140 @@
+﻿use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+use crate::classes::e_action::{Action, Axis};
+use crate::classes::t_input_backend::InputBackend;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VirtualEvent {
+    Press(Action),
+    Release(Action),
+    SetAxis(Axis, f32),
+}
+
+#[derive(Default, Debug)]
+struct VirtualState {
+    down: HashSet<Action>,
+    pressed: HashSet<Action>,
+    released: HashSet<Action>,
+    rotate: f32,
+    thrust: f32,
+
+    time: f32,
+    /// Pending `(time, event)` pairs sorted by time.
+    script: Vec<(f32, VirtualEvent)>,
+}
+
+impl VirtualState {
+    fn apply(&mut self, event: VirtualEvent) {
+        match event {
+            VirtualEvent::Press(action) => {
+                if self.down.insert(action) {
+                    self.pressed.insert(action);
+                }
+            }
+            VirtualEvent::Release(action) => {
+                if self.down.remove(&action) {
+                    self.released.insert(action);
+                }
+            }
+            VirtualEvent::SetAxis(Axis::Rotate, v) => self.rotate = v.clamp(-1.0, 1.0),
+            VirtualEvent::SetAxis(Axis::Thrust, v) => self.thrust = v.clamp(-1.0, 1.0),
+        }
+    }
+}
+
+/// Controller without hardware, for tests, demos and replays. Clones share state, so a test can keep
+/// one handle and give another to `Input::add_backend`. Events are applied directly or scheduled
+/// on the device clock, which advances on every `poll`.
+#[derive(Default, Clone, Debug)]
+pub struct VirtualDevice {
+    state: Rc<RefCell<VirtualState>>,
+}
+
+impl VirtualDevice {
+    /// Queues `event` to fire `at` seconds after the device was created.
+    pub fn schedule(&self, at: f32, event: VirtualEvent) {
+        let mut state = self.state.borrow_mut();
+        let index = state.script.partition_point(|(t, _)| *t <= at);
+        state.script.insert(index, (at, event));
+    }
+
+    /// Builds a device from `Name@start-end` entries separated by commas, e.g. `Thrust@0.5-2,Fire@1,Rotate=-0.5@0-1`.
+    /// Names are actions, or axes with an optional `=value` (default `1`). Times are in seconds;
+    /// without an end an action is tapped for one frame and an axis keeps its value.
+    pub fn from_script(script: &str) -> Result<VirtualDevice, String> {
+        let device = VirtualDevice::default();
+
+        for entry in script.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (name, times) = entry.split_once('@').ok_or(format!("missing '@' in {entry}"))?;
+            let (start, end) = match times.split_once('-') {
+                Some((start, end)) => (start, Some(end)),
+                None => (times, None),
+            };
+
+            let start: f32 = start.parse().map_err(|_| format!("bad time in {entry}"))?;
+            let end: f32 = match end {
+                Some(end) => end.parse().map_err(|_| format!("bad time in {entry}"))?,
+                None => start,
+            };
+
+            if let Some(action) = Action::from_name(name) {
+                device.schedule(start, VirtualEvent::Press(action));
+                // release after the press, even for taps scheduled on the same poll
+                device.schedule(end.max(start) + f32::EPSILON, VirtualEvent::Release(action));
+                continue;
+            }
+
+            let (axis_name, value) = match name.split_once('=') {
+                Some((axis_name, value)) => (axis_name, value.parse().map_err(|_| format!("bad value in {entry}"))?),
+                None => (name, 1.0),
+            };
+            let axis = Axis::from_name(axis_name).ok_or(format!("unknown action or axis {name}"))?;
+            device.schedule(start, VirtualEvent::SetAxis(axis, value));
+            if end > start {
+                device.schedule(end, VirtualEvent::SetAxis(axis, 0.0));
+            }
+        }
+
+        Ok(device)
+    }
+}
+
+impl InputBackend for VirtualDevice {
+    fn poll(&mut self, dt: f32) {
+        let mut state = self.state.borrow_mut();
+        state.time += dt;
+
+        let due = state.script.partition_point(|(t, _)| *t <= state.time);
+        let events: Vec<VirtualEvent> = state.script.drain(..due).map(|(_, e)| e).collect();
+        for event in events {
+            state.apply(event);
+        }
+    }
+
+    fn is_action_down(&self, action: Action) -> bool {
+        self.state.borrow().down.contains(&action)
+    }
+
+    fn is_action_pressed(&self, action: Action) -> bool {
+        self.state.borrow().pressed.contains(&action)
+    }
+
+    fn is_action_released(&self, action: Action) -> bool {
+        self.state.borrow().released.contains(&action)
+    }
+
+    fn get_axis(&self, axis: Axis) -> f32 {
+        let state = self.state.borrow();
+        match axis {
+            Axis::Rotate => state.rotate,
+            Axis::Thrust => state.thrust,
+        }
+    }
+
+    fn end_frame(&mut self) {
+        let mut state = self.state.borrow_mut();
+        state.pressed.clear();
+        state.released.clear();
+    }
+}
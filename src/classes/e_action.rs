@@ -0,0 +1,81 @@
+﻿use serde::{Deserialize, Serialize};
+
+/// Game actions that input bindings map onto.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Action {
+    RotateLeft,
+    RotateRight,
+    Thrust,
+    Reverse,
+    Fire,
+    Hyperspace,
+    Pause,
+
+    MenuUp,
+    MenuDown,
+    MenuLeft,
+    MenuRight,
+    MenuConfirm,
+    MenuBack,
+}
+
+impl Action {
+    pub const ALL: [Action; 13] = [
+        Action::RotateLeft,
+        Action::RotateRight,
+        Action::Thrust,
+        Action::Reverse,
+        Action::Fire,
+        Action::Hyperspace,
+        Action::Pause,
+        Action::MenuUp,
+        Action::MenuDown,
+        Action::MenuLeft,
+        Action::MenuRight,
+        Action::MenuConfirm,
+        Action::MenuBack,
+    ];
+
+    /// Menu actions share keys with gameplay ones (arrows), so bindings only conflict within a group.
+    pub fn is_menu(&self) -> bool {
+        matches!(self, Action::MenuUp | Action::MenuDown | Action::MenuLeft | Action::MenuRight | Action::MenuConfirm | Action::MenuBack)
+    }
+
+    /// Case-insensitive match on the variant name, e.g. `thrust` or `RotateLeft`.
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|a| format!("{:?}", a).eq_ignore_ascii_case(name.trim()))
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::RotateLeft => "Rotate left",
+            Action::RotateRight => "Rotate right",
+            Action::Thrust => "Thrust",
+            Action::Reverse => "Reverse",
+            Action::Fire => "Fire",
+            Action::Hyperspace => "Hyperspace",
+            Action::Pause => "Pause",
+            Action::MenuUp => "Menu up",
+            Action::MenuDown => "Menu down",
+            Action::MenuLeft => "Menu left",
+            Action::MenuRight => "Menu right",
+            Action::MenuConfirm => "Menu select",
+            Action::MenuBack => "Menu back",
+        }
+    }
+}
+
+/// Analog controls; digital devices drive them through the paired actions.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Axis {
+    /// Negative turns left, positive turns right.
+    Rotate,
+    /// Positive is forward thrust, negative reverse.
+    Thrust,
+}
+
+impl Axis {
+    pub fn from_name(name: &str) -> Option<Axis> {
+        [Axis::Rotate, Axis::Thrust].into_iter().find(|a| format!("{:?}", a).eq_ignore_ascii_case(name.trim()))
+    }
+}
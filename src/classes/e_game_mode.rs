@@ -0,0 +1,22 @@
+﻿use serde::{Deserialize, Serialize};
+
+/// Rules of a game session.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum GameMode {
+    #[default]
+    Solo,
+    /// Two players on one keyboard share the field and the waves, each keeps their own score.
+    Coop,
+    /// Deathmatch, player bullets hit the other ships and rounds end at a kill limit.
+    Versus { players: usize },
+}
+
+impl GameMode {
+    pub fn player_count(&self) -> usize {
+        match self {
+            GameMode::Solo => 1,
+            GameMode::Coop => 2,
+            GameMode::Versus { players } => *players,
+        }
+    }
+}
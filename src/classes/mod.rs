@@ -9,5 +9,23 @@ mod bullet_entity;
 mod c_asteroid_entity;
 mod c_enemy_entity;
 mod c_scene_test;
+mod c_scene_editor;
 mod c_debris_entity;
 mod c_audio_context;
+
+mod c_score_popup_entity;
+pub mod c_headless_handler;
+pub mod e_action;
+pub mod c_input_bindings;
+pub mod t_input_backend;
+pub mod c_keyboard_backend;
+pub mod c_virtual_device;
+pub mod c_gamepad_settings;
+#[cfg(feature = "gamepad")]
+pub mod c_gamepad_backend;
+pub mod c_menu_nav;
+pub mod e_game_mode;
+pub mod c_round_manager;
+pub mod c_scene_pause;
+pub mod c_ai_pilot;
+pub mod c_attract_demo;
@@ -17,4 +17,8 @@ pub trait Entity : Drawable + Collide{
     }
 
     fn get_position(&self) -> &Vec2<f32>;
+
+    fn get_velocity(&self) -> Vec2<f32> {
+        Vec2::zero()
+    }
 }
\ No newline at end of file
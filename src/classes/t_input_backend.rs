@@ -0,0 +1,33 @@
+﻿use crate::classes::e_action::{Action, Axis};
+
+/// A device that feeds the action map: keyboard, gamepad or a scripted virtual device.
+/// `Input` merges all backends, actions are down when any backend holds them.
+pub trait InputBackend {
+    /// Called once per frame before the backend is queried.
+    fn poll(&mut self, _dt: f32) {}
+
+    /// `false` while the device has nothing to read from, e.g. a gamepad slot without a pad.
+    fn is_connected(&self) -> bool {
+        true
+    }
+
+    fn is_action_down(&self, action: Action) -> bool;
+
+    /// `true` when the action went down since the last `end_frame`, including taps shorter than a frame.
+    /// Backends without sub-frame events leave it to `Input`, which compares frames.
+    fn is_action_pressed(&self, _action: Action) -> bool {
+        false
+    }
+
+    /// `true` when the action went up since the last `end_frame`, see `is_action_pressed`.
+    fn is_action_released(&self, _action: Action) -> bool {
+        false
+    }
+
+    /// Analog value in `-1..=1`, already past the device deadzone.
+    fn get_axis(&self, _axis: Axis) -> f32 {
+        0.0
+    }
+
+    fn end_frame(&mut self) {}
+}
@@ -0,0 +1,61 @@
+﻿use crate::collisions_lib::col_solver::{COLLISION_TABLE, LAYER_COUNT};
+use crate::collisions_lib::e_col_layers::ColLayer;
+
+/// Which bodies may hit each other: the layer table plus ownership, so a player is never
+/// hit by their own bullets even where player bullets and ships collide.
+#[derive(Clone, Copy, Debug)]
+pub struct CollisionRules {
+    table: [[bool; LAYER_COUNT]; LAYER_COUNT],
+}
+
+impl Default for CollisionRules {
+    fn default() -> Self {
+        Self { table: COLLISION_TABLE }
+    }
+}
+
+impl CollisionRules {
+    /// Player bullets hit other players' ships.
+    pub fn versus() -> Self {
+        let mut rules = Self::default();
+        rules.set(ColLayer::Player, ColLayer::BulletPlayer, true);
+        rules
+    }
+
+    pub fn set(&mut self, a: ColLayer, b: ColLayer, collide: bool) {
+        self.table[a.idx()][b.idx()] = collide;
+        self.table[b.idx()][a.idx()] = collide;
+    }
+
+    /// `owner` is the local player a body belongs to, see `Collide::get_owner`.
+    pub fn allows(&self, a: (ColLayer, Option<usize>), b: (ColLayer, Option<usize>)) -> bool {
+        if !self.table[a.0.idx()][b.0.idx()] {
+            return false;
+        }
+        match (a.1, b.1) {
+            (Some(owner_a), Some(owner_b)) => owner_a != owner_b,
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versus_bullets_skip_their_owner() {
+        let ship = |p| (ColLayer::Player, Some(p));
+        let bullet = |p| (ColLayer::BulletPlayer, Some(p));
+
+        let coop = CollisionRules::default();
+        assert!(!coop.allows(ship(0), bullet(1)));
+        assert!(coop.allows(bullet(0), (ColLayer::Asteroid, None)));
+
+        let versus = CollisionRules::versus();
+        assert!(versus.allows(ship(0), bullet(1)));
+        assert!(versus.allows(bullet(1), ship(0)));
+        assert!(!versus.allows(ship(1), bullet(1)));
+        assert!(!versus.allows(bullet(0), bullet(1)));
+    }
+}
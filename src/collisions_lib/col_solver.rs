@@ -1,7 +1,10 @@
 ﻿use std::collections::HashMap;
 use crate::classes::t_entity::Entity;
-use crate::collisions_lib::e_col_layers::ColLayer;
+use crate::collisions_lib::c_collision_rules::CollisionRules;
+use crate::collisions_lib::t_collision::Contact;
+use crate::mesh_lib::c_mesh::{Aabb, Mesh};
 use crate::scenes_lib::e_scene_event::SceneEvent;
+use crate::transform_lib::c_transform::Transform;
 
 
 
@@ -16,7 +19,7 @@ pub const COLLISION_TABLE: [[bool; LAYER_COUNT]; LAYER_COUNT] = [
     /* Enemy*/   [true,   true,    true,   false, false],
 ];
 
-pub fn solve_collision(entity: &mut Vec<Box<dyn Entity>>) -> Vec<SceneEvent> {
+pub fn solve_collision(entity: &mut Vec<Box<dyn Entity>>, rules: &CollisionRules) -> Vec<SceneEvent> {
     let mut events = vec![];
 
     for i in 0..entity.len() {
@@ -26,11 +29,11 @@ pub fn solve_collision(entity: &mut Vec<Box<dyn Entity>>) -> Vec<SceneEvent> {
 
             if (entity2.get_entity_id() != entity1.get_entity_id()) {
                 if (entity1.can_collide() && entity2.can_collide()) {
-                    let layer1 = entity1.get_collision_layer();
-                    let layer2 = entity2.get_collision_layer();
+                    let body1 = (entity1.get_collision_layer(), entity1.get_owner());
+                    let body2 = (entity2.get_collision_layer(), entity2.get_owner());
 
 
-                    if (COLLISION_TABLE[layer1.idx()][layer2.idx()]) {
+                    if rules.allows(body1, body2) {
                         let col_mesh_1 = entity1.get_collision_mesh();
                         let col_mesh_2 = entity2.get_collision_mesh();
 
@@ -39,22 +42,11 @@ pub fn solve_collision(entity: &mut Vec<Box<dyn Entity>>) -> Vec<SceneEvent> {
                             let (mesh1, transform1) = col_mesh_1.unwrap();
                             let (mesh2, transform2) = col_mesh_2.unwrap();
 
-                            for line_col1 in mesh1.get_lines() {
-                                let a = transform1.transform_point_to_world(line_col1.start);
-                                let b = transform1.transform_point_to_world(line_col1.end);
-
-                                for line_col2 in mesh2.get_lines() {
-                                    let c = transform2.transform_point_to_world(line_col2.start);
-                                    let d = transform2.transform_point_to_world(line_col2.end);
-
-                                    if seg_intersect(a, b, c, d) {
-                                        events.push(SceneEvent::Collision {
-                                            a: entity1.get_entity_id(),
-                                            b: entity2.get_entity_id(),
-                                        });
-                                        break;
-                                    }
-                                }
+                            if meshes_intersect_wrapped(&mesh1, transform1, &mesh2, transform2) {
+                                events.push(SceneEvent::Collision {
+                                    a: entity1.get_entity_id(),
+                                    b: entity2.get_entity_id(),
+                                });
                             }
                         }
                     }
@@ -67,17 +59,17 @@ pub fn solve_collision(entity: &mut Vec<Box<dyn Entity>>) -> Vec<SceneEvent> {
     for event in &events {
         if let SceneEvent::Collision { a, b } = event {
             
-            let (layer_a, layer_b) = {
+            let (contact_a, contact_b) = {
                 let ea = entity.iter().find(|e| e.get_entity_id() == *a).unwrap();
                 let eb = entity.iter().find(|e| e.get_entity_id() == *b).unwrap();
-                (ea.get_collision_layer(), eb.get_collision_layer())
+                (contact_of(ea.as_ref()), contact_of(eb.as_ref()))
             };
 
             if let Some(ea) = entity.iter_mut().find(|e| e.get_entity_id() == *a) {
-                ea.on_collision(layer_b);
+                ea.on_contact(&contact_b);
             }
             if let Some(eb) = entity.iter_mut().find(|e| e.get_entity_id() == *b) {
-                eb.on_collision(layer_a);
+                eb.on_contact(&contact_a);
             }
         }
     }
@@ -86,6 +78,62 @@ pub fn solve_collision(entity: &mut Vec<Box<dyn Entity>>) -> Vec<SceneEvent> {
     events
 }
 
+fn contact_of(entity: &dyn Entity) -> Contact {
+    Contact {
+        layer: entity.get_collision_layer(),
+        position: *entity.get_position(),
+        owner: entity.get_owner(),
+    }
+}
+
+/// Tests every wrap ghost of one mesh against every ghost of the other,
+/// so bodies straddling a screen edge collide on both sides.
+pub fn meshes_intersect_wrapped(mesh1: &Mesh, transform1: &Transform, mesh2: &Mesh, transform2: &Transform) -> bool {
+    let radius1 = mesh1.bounding_radius() * transform1.get_max_scale();
+    let radius2 = mesh2.bounding_radius() * transform2.get_max_scale();
+
+    for ghost1 in transform1.get_wrap_ghosts(radius1) {
+        for ghost2 in transform2.get_wrap_ghosts(radius2) {
+            let dist = ghost1.get_position().distance(*ghost2.get_position());
+            if dist > radius1 + radius2 {
+                continue;
+            }
+
+            if meshes_intersect(mesh1, &ghost1, mesh2, &ghost2) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+pub fn meshes_intersect(mesh1: &Mesh, transform1: &Transform, mesh2: &Mesh, transform2: &Transform) -> bool {
+    let world1 = world_segments(mesh1, transform1);
+    let world2 = world_segments(mesh2, transform2);
+
+    let bounds1 = Aabb::from_points(world1.iter().flat_map(|(a, b)| [*a, *b]));
+    let bounds2 = Aabb::from_points(world2.iter().flat_map(|(a, b)| [*a, *b]));
+    match (bounds1, bounds2) {
+        (Some(b1), Some(b2)) if b1.intersects(&b2) => {}
+        _ => return false,
+    }
+
+    for (a, b) in world1.iter() {
+        for (c, d) in world2.iter() {
+            if seg_intersect(*a, *b, *c, *d) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+fn world_segments(mesh: &Mesh, transform: &Transform) -> Vec<(vek::Vec2<f32>, vek::Vec2<f32>)> {
+    mesh.get_lines()
+        .iter()
+        .map(|l| (transform.transform_point_to_world(l.start), transform.transform_point_to_world(l.end)))
+        .collect()
+}
 
 #[inline]
 fn cross(a: vek::Vec2<f32>, b: vek::Vec2<f32>) -> f32 {
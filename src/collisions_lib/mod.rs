@@ -1,3 +1,4 @@
 ﻿pub mod col_solver;
 pub mod t_collision;
-pub mod e_col_layers;
\ No newline at end of file
+pub mod e_col_layers;
+pub mod c_collision_rules;
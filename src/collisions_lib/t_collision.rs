@@ -1,6 +1,7 @@
 ﻿use crate::collisions_lib::e_col_layers::ColLayer;
 use crate::mesh_lib::c_mesh::Mesh;
 use std::rc::Rc;
+use vek::Vec2;
 use crate::transform_lib::c_transform::Transform;
 
 pub trait Collide {
@@ -17,4 +18,22 @@ pub trait Collide {
     fn on_collision(&mut self, layer: ColLayer) {
         
     }
-}
\ No newline at end of file
+
+    /// Local player that owns this body (the ship itself or its bullets), used for scoring.
+    fn get_owner(&self) -> Option<usize> {
+        None
+    }
+
+    /// Same as `on_collision`, with the details of the other body.
+    fn on_contact(&mut self, contact: &Contact) {
+        self.on_collision(contact.layer);
+    }
+}
+
+/// The other side of a collision.
+#[derive(Clone, Copy, Debug)]
+pub struct Contact {
+    pub layer: ColLayer,
+    pub position: Vec2<f32>,
+    pub owner: Option<usize>,
+}
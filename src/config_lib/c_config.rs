@@ -1,41 +1,132 @@
-﻿use serde::{Deserialize, Serialize};
+﻿use std::io;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
 use vek::Vec2;
+use crate::assetsdb_lib::c_assets_db::AssetsDB;
 use crate::assetsdb_lib::e_asset::Asset;
+use crate::assetsdb_lib::json_utils::{read_json_file, write_json_file};
 use crate::assetsdb_lib::t_file_readable::FileReadable;
 use crate::assetsdb_lib::t_file_writable::FileWritable;
 use crate::assetsdb_lib::t_from_assetref::FromAssetRef;
+use crate::classes::c_gamepad_settings::GamepadSettings;
+use crate::classes::c_input_bindings::InputBindings;
 
-#[derive(Serialize, Deserialize, Copy, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Config{
+    /// Logical playfield size. Everything in the game lives in these coordinates,
+    /// the window is letterboxed around it.
     window_size: Vec2<usize>,
-    read_only_actual_size: Vec2<usize>
+    /// Physical surface size in pixels, updated by the app on resize.
+    read_only_actual_size: Vec2<usize>,
+    #[serde(default)]
+    fullscreen: bool,
+    /// Rasterizer worker threads, `0` uses every core.
+    #[serde(default)]
+    raster_threads: usize,
+    /// Keys bound to each game action, one entry per keyboard player, edited from the menu
+    /// controls screen.
+    #[serde(default = "default_bindings")]
+    bindings: Vec<InputBindings>,
+    /// Deadzones and button bindings for the `gamepad` feature.
+    #[serde(default)]
+    gamepad: GamepadSettings,
+}
+
+/// Settings changed from inside the game. They live in `user.cfg` in the working directory
+/// and are laid over the shipped `data/config.cfg`, which is never written back.
+#[derive(Serialize, Deserialize)]
+struct UserSettings {
+    bindings: Vec<InputBindings>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self{
             window_size: Vec2::new(800, 600),
-            read_only_actual_size: Vec2::new(800, 600)
+            read_only_actual_size: Vec2::new(800, 600),
+            fullscreen: false,
+            raster_threads: 0,
+            bindings: default_bindings(),
+            gamepad: GamepadSettings::default(),
         }
     }
 }
 
-impl Config{    
+fn default_bindings() -> Vec<InputBindings> {
+    (0..KEYBOARD_PLAYERS).map(InputBindings::for_player).collect()
+}
+
+/// Local players that have keyboard bindings in the config.
+pub const KEYBOARD_PLAYERS: usize = 2;
+
+impl Config{
     pub fn new(window_size: Vec2<usize>) -> Config{
-        Config{window_size, read_only_actual_size: window_size}
+        Config{window_size, read_only_actual_size: window_size, ..Default::default()}
     }
 
     pub fn size(&self) -> Vec2<usize>{
-        self.read_only_actual_size
+        self.window_size
     }
-    
-    pub fn x(&self) -> usize{self.read_only_actual_size.x}
 
-    pub fn y(&self) -> usize{self.read_only_actual_size.y}
-    
+    pub fn x(&self) -> usize{self.window_size.x}
+
+    pub fn y(&self) -> usize{self.window_size.y}
+
     pub fn set_actual_size(&mut self, size: Vec2<usize>){
         self.read_only_actual_size = size;
     }
+
+    pub fn is_fullscreen(&self) -> bool {
+        self.fullscreen
+    }
+
+    pub fn raster_threads(&self) -> usize {
+        self.raster_threads
+    }
+
+    /// Keyboard bindings of local player `player`, `0` is the first. `None` for players past
+    /// `KEYBOARD_PLAYERS`, they play on their own devices.
+    pub fn bindings(&self, player: usize) -> Option<&InputBindings> {
+        self.bindings.get(player)
+    }
+
+    pub fn bindings_mut(&mut self, player: usize) -> Option<&mut InputBindings> {
+        self.bindings.get_mut(player)
+    }
+
+    /// Bindings for every keyboard player, with defaults for players and actions the file lacks.
+    pub fn fill_missing_bindings(&mut self) {
+        self.bindings.truncate(KEYBOARD_PLAYERS);
+        for player in self.bindings.len()..KEYBOARD_PLAYERS {
+            self.bindings.push(InputBindings::for_player(player));
+        }
+        for (player, bindings) in self.bindings.iter_mut().enumerate() {
+            bindings.fill_missing(player);
+        }
+    }
+
+    pub fn gamepad(&self) -> &GamepadSettings {
+        &self.gamepad
+    }
+
+    pub fn user_settings_path() -> PathBuf {
+        AssetsDB::get_curr_dir().join("user.cfg")
+    }
+
+    /// Applies saved user settings, a missing file keeps the shipped ones.
+    pub fn load_user_settings(&mut self, path: &Path) {
+        if !path.exists() {
+            return;
+        }
+        match read_json_file::<UserSettings>(&path) {
+            Ok(settings) => self.bindings = settings.bindings,
+            Err(e) => println!("Error reading user settings {}: {:?}", path.display(), e),
+        }
+    }
+
+    pub fn save_user_settings(&self, path: &Path) -> io::Result<()> {
+        write_json_file(&path, &UserSettings { bindings: self.bindings.clone() })
+    }
 }
 
 impl FileWritable for Config{}
@@ -48,4 +139,41 @@ impl FromAssetRef for Config {
             _ => None,
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use winit::keyboard::KeyCode;
+    use crate::classes::c_input::MAX_PLAYERS;
+    use crate::classes::e_action::Action;
+
+    #[test]
+    fn bindings_cover_keyboard_players_only() {
+        let mut config: Config = serde_json::from_str(
+            r#"{"window_size":{"x":800,"y":600},"read_only_actual_size":{"x":800,"y":600},"bindings":[{"Fire":["KeyF"]}]}"#
+        ).unwrap();
+        config.fill_missing_bindings();
+
+        assert_eq!(config.bindings(0).unwrap().keys(Action::Fire), [KeyCode::KeyF]);
+        assert_eq!(config.bindings(1).unwrap().keys(Action::Fire), InputBindings::for_player(1).keys(Action::Fire));
+        for player in KEYBOARD_PLAYERS..MAX_PLAYERS {
+            assert!(config.bindings(player).is_none());
+        }
+    }
+
+    #[test]
+    fn user_settings_only_carry_bindings() {
+        let path = std::env::temp_dir().join(format!("asteroids_user_{}.cfg", std::process::id()));
+        let mut config = Config::new(Vec2::new(320, 240));
+        config.bindings_mut(1).unwrap().bind(Action::Fire, 0, KeyCode::KeyF);
+        config.save_user_settings(&path).unwrap();
+
+        let mut loaded = Config::default();
+        loaded.load_user_settings(&path);
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(loaded.bindings(1), config.bindings(1));
+        assert_eq!(loaded.size(), Vec2::new(800, 600));
+    }
+}
@@ -0,0 +1,61 @@
+﻿use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use vek::Vec2;
+use crate::assetsdb_lib::e_asset::Asset;
+use crate::assetsdb_lib::t_file_readable::FileReadable;
+use crate::assetsdb_lib::t_file_writable::FileWritable;
+use crate::assetsdb_lib::t_from_assetref::FromAssetRef;
+use crate::mesh_lib::c_mesh::Mesh;
+
+/// Monospace stroke font, every glyph is an unfilled `Mesh` in a `cell` sized box (y goes down).
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct StrokeFont {
+    name: String,
+    cell: Vec2<f32>,
+    advance: f32,
+    line_height: f32,
+    glyphs: HashMap<char, Mesh>,
+}
+
+impl FileWritable for StrokeFont {}
+
+impl FileReadable for StrokeFont {}
+
+impl FromAssetRef for StrokeFont {
+    fn from_asset(a: &Asset) -> Option<&Self> {
+        match a {
+            Asset::Font(f) => Some(f),
+            _ => None,
+        }
+    }
+}
+
+impl StrokeFont {
+    pub fn get_advance(&self) -> f32 { self.advance }
+
+    pub fn get_line_height(&self) -> f32 { self.line_height }
+
+    /// Falls back to the upper case glyph, fonts usually ship capitals only.
+    pub fn get_glyph(&self, ch: char) -> Option<&Mesh> {
+        self.glyphs
+            .get(&ch)
+            .or_else(|| self.glyphs.get(&ch.to_ascii_uppercase()))
+    }
+
+    /// Size of the text block in pixels for the given scale.
+    pub fn measure(&self, text: &str, scale: f32) -> Vec2<f32> {
+        let lines = text.split('\n');
+        let mut width: f32 = 0.0;
+        let mut count = 0;
+        for line in lines {
+            let chars = line.chars().count();
+            if chars > 0 {
+                width = width.max((chars - 1) as f32 * self.advance + self.cell.x);
+            }
+            count += 1;
+        }
+
+        let height = (count - 1) as f32 * self.line_height + self.cell.y;
+        Vec2::new(width, height) * scale
+    }
+}
@@ -0,0 +1 @@
+﻿pub mod c_stroke_font;
\ No newline at end of file
@@ -9,14 +9,32 @@ mod uniq_id_lib;
 mod collisions_lib;
 mod sprite_lib;
 mod web_lib;
+mod font_lib;
+mod random_lib;
+mod net_lib;
 
 use crate::classes::c_app_handler::AppHandler;
 use crate::classes::c_game::Game;
+use crate::classes::c_headless_handler::{HeadlessHandler, HeadlessOptions};
 use crate::classes::c_input::Input;
 
 fn main() {
+    let headless = match HeadlessOptions::from_args(std::env::args()) {
+        Ok(headless) => headless,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(2);
+        }
+    };
+
     let mut input = Input::default();
     let mut game = Game::new();
+
+    if let Some(options) = headless {
+        HeadlessHandler::new(options).run(&mut game, &mut input);
+        return;
+    }
+
     let mut apphost = AppHandler::default();
     apphost.run(&mut game, &mut input);
 }
@@ -0,0 +1,71 @@
+﻿use std::f32::consts::TAU;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use vek::Vec2;
+use crate::mesh_lib::c_mesh::{Mesh, MeshLine};
+
+/// Parameters of a generated asteroid outline.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug)]
+pub struct AsteroidShape {
+    pub vertex_count: u32,
+    /// `0` is a regular polygon, `1` lets vertices sink to the center.
+    pub roughness: f32,
+    /// Outer radius in mesh units, vertices never go past it.
+    pub radius: f32,
+}
+
+impl Default for AsteroidShape {
+    fn default() -> Self {
+        AsteroidShape {
+            vertex_count: 12,
+            roughness: 0.35,
+            radius: 70.0,
+        }
+    }
+}
+
+impl AsteroidShape {
+    /// Shape for an asteroid spawned at `scale`: small rocks get fewer, sharper corners
+    /// so they still read as jagged once scaled down.
+    pub fn for_scale(scale: f32) -> AsteroidShape {
+        let t = scale.clamp(0.0, 1.0);
+        AsteroidShape {
+            vertex_count: (7.0 + 7.0 * t).round() as u32,
+            roughness: 0.5 - 0.2 * t,
+            ..AsteroidShape::default()
+        }
+    }
+}
+
+/// Builds a closed, non self-intersecting jagged outline around the origin.
+/// The same seed and shape always give the same mesh.
+pub fn generate_asteroid(seed: u64, shape: &AsteroidShape) -> Mesh {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let count = shape.vertex_count.max(3);
+    let roughness = shape.roughness.clamp(0.0, 0.95);
+    let step = TAU / count as f32;
+    let phase = rng.random_range(0.0..TAU);
+
+    // angles stay monotonic (jitter below half a step), so the polygon is star shaped around the origin
+    let vertices: Vec<Vec2<f32>> = (0..count)
+        .map(|i| {
+            let angle = phase + (i as f32 + rng.random_range(-0.4..0.4)) * step;
+            let mut r = 1.0 - roughness * rng.random_range(0.0..1.0f32);
+
+            // an occasional deep notch reads as a crater
+            if roughness > 0.0 && rng.random_range(0.0..1.0f32) < 0.15 {
+                r *= 1.0 - roughness * 0.5;
+            }
+
+            Vec2::new(angle.cos(), angle.sin()) * r * shape.radius
+        })
+        .collect();
+
+    let lines = vertices.iter()
+        .enumerate()
+        .map(|(i, v)| MeshLine::new(*v, vertices[(i + 1) % vertices.len()]))
+        .collect();
+
+    Mesh::new(format!("asteroid_{seed}"), lines, false)
+}
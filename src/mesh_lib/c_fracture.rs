@@ -0,0 +1,157 @@
+﻿use std::f32::consts::PI;
+use rand::Rng;
+use vek::Vec2;
+use crate::mesh_lib::c_mesh::{point_in_polygon, polygon_centroid, polygon_signed_area, Mesh, MeshLine};
+
+/// Cut vertices closer than this to a chord are nudged off it.
+const CHORD_EPSILON: f32 = 1e-3;
+/// Pieces smaller than this fraction of the parent area are dropped.
+const MIN_AREA_FRACTION: f32 = 0.002;
+
+/// One piece of a fractured mesh.
+pub struct Fragment {
+    /// Outline recentered on its own centroid.
+    pub mesh: Mesh,
+    /// Centroid of the piece in the parent's local space.
+    pub offset: Vec2<f32>,
+    /// Piece area divided by the parent area.
+    pub area_ratio: f32,
+}
+
+impl Fragment {
+    /// Uniform scale a whole parent would need to have the same area as this piece.
+    pub fn area_scale(&self) -> f32 {
+        self.area_ratio.sqrt()
+    }
+}
+
+/// Cuts the closed outline of `mesh` with `chords` random lines through `impact` (local space).
+/// Chords are spread around the half turn, so two chords give four wedge-like pieces.
+/// Only the largest closed outline is cut, returns nothing when the mesh has none.
+pub fn fracture_mesh(mesh: &Mesh, impact: Vec2<f32>, chords: u32, rng: &mut impl Rng) -> Vec<Fragment> {
+    let Some(outline) = mesh.polylines().into_iter()
+        .filter(|p| p.closed)
+        .max_by(|a, b| polygon_area(&a.points).total_cmp(&polygon_area(&b.points)))
+        .map(|p| p.points) else {
+        return vec![];
+    };
+
+    let total_area = polygon_area(&outline);
+    if total_area <= f32::EPSILON {
+        return vec![];
+    }
+
+    let center = if point_in_polygon(impact, &outline) { impact } else { polygon_centroid(&outline) };
+    let chords = chords.max(1);
+    let first = rng.random_range(0.0..PI);
+
+    let mut pieces = vec![outline];
+    for i in 0..chords {
+        let jitter = rng.random_range(-0.25..0.25) * PI / chords as f32;
+        let angle = first + i as f32 * PI / chords as f32 + jitter;
+        let dir = Vec2::new(angle.cos(), angle.sin());
+
+        pieces = pieces.iter()
+            .flat_map(|piece| split_polygon(piece, center, dir))
+            .collect();
+    }
+
+    pieces.into_iter()
+        .filter_map(|piece| {
+            let area = polygon_area(&piece);
+            if area < total_area * MIN_AREA_FRACTION {
+                return None;
+            }
+
+            let centroid = polygon_centroid(&piece);
+            let lines = piece.iter()
+                .enumerate()
+                .map(|(i, v)| MeshLine::new(*v - centroid, piece[(i + 1) % piece.len()] - centroid))
+                .collect();
+
+            Some(Fragment {
+                mesh: Mesh::new(format!("{}_fragment", mesh.get_name()), lines, mesh.is_filled()),
+                offset: centroid,
+                area_ratio: area / total_area,
+            })
+        })
+        .collect()
+}
+
+/// Splits a simple polygon by the infinite line through `point` along `dir`.
+/// Crossings are paired along the line, so concave outlines may give more than two pieces.
+fn split_polygon(polygon: &[Vec2<f32>], point: Vec2<f32>, dir: Vec2<f32>) -> Vec<Vec<Vec2<f32>>> {
+    let normal = Vec2::new(-dir.y, dir.x);
+    let side: Vec<f32> = polygon.iter()
+        .map(|v| {
+            let d = (*v - point).dot(normal);
+            if d.abs() < CHORD_EPSILON { CHORD_EPSILON } else { d }
+        })
+        .collect();
+
+    struct Node {
+        pos: Vec2<f32>,
+        partner: Option<usize>,
+    }
+
+    let mut ring: Vec<Node> = Vec::with_capacity(polygon.len() * 2);
+    let mut crossings: Vec<(f32, usize)> = vec![];
+
+    for i in 0..polygon.len() {
+        let j = (i + 1) % polygon.len();
+        ring.push(Node { pos: polygon[i], partner: None });
+
+        if (side[i] > 0.0) != (side[j] > 0.0) {
+            let t = side[i] / (side[i] - side[j]);
+            let pos = polygon[i] + (polygon[j] - polygon[i]) * t;
+            crossings.push(((pos - point).dot(dir), ring.len()));
+            ring.push(Node { pos, partner: None });
+        }
+    }
+
+    if crossings.len() < 2 {
+        return vec![polygon.to_vec()];
+    }
+
+    // inside a simple polygon the line alternates in/out, consecutive crossings bound one cut
+    crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+    for pair in crossings.chunks_exact(2) {
+        ring[pair[0].1].partner = Some(pair[1].1);
+        ring[pair[1].1].partner = Some(pair[0].1);
+    }
+
+    let mut visited = vec![false; ring.len()];
+    let mut pieces = vec![];
+
+    for start in 0..ring.len() {
+        if visited[start] || ring[start].partner.is_some() {
+            continue;
+        }
+
+        let mut piece = vec![];
+        let mut cur = start;
+        for _ in 0..ring.len() * 2 {
+            visited[cur] = true;
+            piece.push(ring[cur].pos);
+
+            if let Some(partner) = ring[cur].partner {
+                cur = partner;
+                piece.push(ring[cur].pos);
+            }
+
+            cur = (cur + 1) % ring.len();
+            if cur == start {
+                break;
+            }
+        }
+
+        if piece.len() >= 3 {
+            pieces.push(piece);
+        }
+    }
+    pieces
+}
+
+fn polygon_area(polygon: &[Vec2<f32>]) -> f32 {
+    polygon_signed_area(polygon).abs()
+}
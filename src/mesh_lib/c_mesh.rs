@@ -2,6 +2,7 @@
 use crate::assetsdb_lib::t_file_readable::FileReadable;
 use crate::assetsdb_lib::t_file_writable::FileWritable;
 use crate::assetsdb_lib::t_from_assetref::FromAssetRef;
+use crate::mesh_lib::c_mesh_format::MeshFile;
 use serde::{Deserialize, Serialize};
 use vek::Vec2;
 
@@ -20,7 +21,10 @@ impl MeshLine {
     }    
 }
 
+/// Unordered set of line segments in local space. Serialized through `MeshFile`,
+/// which reads both `.mesh` layouts and writes the compact v2 one.
 #[derive(Serialize, Deserialize, Clone)]
+#[serde(try_from = "MeshFile", into = "MeshFile")]
 #[derive(Default)]
 pub struct Mesh{
     name: String,
@@ -54,5 +58,384 @@ impl Mesh {
     pub fn get_lines(&self) -> &Vec<MeshLine> { &self.points }
     
     pub fn get_name(&self) -> &String { &self.name }
+
+    /// Distance from the local origin to the farthest vertex.
+    pub fn bounding_radius(&self) -> f32 {
+        self.points
+            .iter()
+            .map(|line| line.start.magnitude().max(line.end.magnitude()))
+            .fold(0.0, f32::max)
+    }
+
+    /// Local space bounds, `None` for an empty mesh.
+    pub fn aabb(&self) -> Option<Aabb> {
+        Aabb::from_points(self.points.iter().flat_map(|l| [l.start, l.end]))
+    }
+
+    /// Unique endpoints, vertices closer than `WELD_EPSILON` count as one.
+    pub fn vertices(&self) -> Vec<Vec2<f32>> {
+        let mut vertices: Vec<Vec2<f32>> = vec![];
+        for p in self.points.iter().flat_map(|l| [l.start, l.end]) {
+            if !vertices.iter().any(|v| v.distance(p) <= WELD_EPSILON) {
+                vertices.push(p);
+            }
+        }
+        vertices
+    }
+
+    /// Chains segments sharing endpoints into polylines. Open chains start at a free end,
+    /// chains that come back to their first vertex are marked closed.
+    pub fn polylines(&self) -> Vec<Polyline> {
+        let lines = &self.points;
+        let mut used = vec![false; lines.len()];
+        let mut result = vec![];
+
+        let degree = |p: Vec2<f32>| lines.iter()
+            .filter(|l| l.start.distance(p) <= WELD_EPSILON || l.end.distance(p) <= WELD_EPSILON)
+            .count();
+
+        // free ends first so open chains are not started in the middle
+        let mut starts: Vec<(usize, bool)> = vec![];
+        for (i, l) in lines.iter().enumerate() {
+            if degree(l.start) == 1 { starts.push((i, false)); }
+            if degree(l.end) == 1 { starts.push((i, true)); }
+        }
+        starts.extend((0..lines.len()).map(|i| (i, false)));
+
+        for (first, reversed) in starts {
+            if used[first] {
+                continue;
+            }
+            used[first] = true;
+
+            let (a, b) = if reversed { (lines[first].end, lines[first].start) } else { (lines[first].start, lines[first].end) };
+            let mut points = vec![a, b];
+
+            loop {
+                let cursor = *points.last().unwrap();
+                if points.len() > 2 && cursor.distance(points[0]) <= WELD_EPSILON {
+                    break;
+                }
+
+                let next = lines.iter().enumerate().find_map(|(i, l)| {
+                    if used[i] {
+                        None
+                    } else if l.start.distance(cursor) <= WELD_EPSILON {
+                        Some((i, l.end))
+                    } else if l.end.distance(cursor) <= WELD_EPSILON {
+                        Some((i, l.start))
+                    } else {
+                        None
+                    }
+                });
+
+                let Some((i, p)) = next else { break };
+                used[i] = true;
+                points.push(p);
+            }
+
+            let closed = points.len() > 3 && points[0].distance(*points.last().unwrap()) <= WELD_EPSILON;
+            if closed {
+                points.pop();
+            }
+            result.push(Polyline { points, closed });
+        }
+        result
+    }
+
+    /// Enclosed area of all closed outlines.
+    pub fn area(&self) -> f32 {
+        self.polylines().iter()
+            .filter(|p| p.closed)
+            .map(|p| polygon_signed_area(&p.points).abs())
+            .sum()
+    }
+
+    /// Area weighted centroid of the closed outlines, vertex average for open meshes.
+    pub fn centroid(&self) -> Vec2<f32> {
+        let mut weighted = Vec2::zero();
+        let mut total = 0.0;
+        for polyline in self.polylines().iter().filter(|p| p.closed) {
+            let area = polygon_signed_area(&polyline.points).abs();
+            weighted += polygon_centroid(&polyline.points) * area;
+            total += area;
+        }
+
+        if total > f32::EPSILON {
+            return weighted / total;
+        }
+
+        let vertices = self.vertices();
+        vertices.iter().copied().sum::<Vec2<f32>>() / vertices.len().max(1) as f32
+    }
+
+    /// Counter-clockwise convex hull of the vertices (monotone chain).
+    pub fn convex_hull(&self) -> Vec<Vec2<f32>> {
+        let mut points = self.vertices();
+        if points.len() < 3 {
+            return points;
+        }
+        points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+
+        let turn = |o: Vec2<f32>, a: Vec2<f32>, b: Vec2<f32>| (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x);
+        let mut hull: Vec<Vec2<f32>> = Vec::with_capacity(points.len() * 2);
+
+        for pass in [points.clone(), points.into_iter().rev().collect()] {
+            let base = hull.len();
+            for p in pass {
+                while hull.len() >= base + 2 && turn(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0.0 {
+                    hull.pop();
+                }
+                hull.push(p);
+            }
+            hull.pop();
+        }
+        hull
+    }
+
+    /// Douglas–Peucker simplification of every polyline, `tolerance` in mesh units.
+    pub fn simplified(&self, tolerance: f32) -> Mesh {
+        let polylines: Vec<Polyline> = self.polylines().into_iter()
+            .map(|p| {
+                if !p.closed {
+                    return Polyline { points: douglas_peucker(&p.points, tolerance), closed: false };
+                }
+
+                // split the loop at its farthest vertex so both halves have fixed ends
+                let far = (1..p.points.len())
+                    .max_by(|a, b| p.points[*a].distance(p.points[0]).total_cmp(&p.points[*b].distance(p.points[0])))
+                    .unwrap_or(0);
+                let mut first = douglas_peucker(&p.points[..=far], tolerance);
+                let mut second: Vec<Vec2<f32>> = p.points[far..].to_vec();
+                second.push(p.points[0]);
+                let second = douglas_peucker(&second, tolerance);
+
+                first.pop();
+                first.extend_from_slice(&second[..second.len() - 1]);
+                if first.len() < 3 {
+                    return p;
+                }
+                Polyline { points: first, closed: true }
+            })
+            .collect();
+
+        Mesh::from_polylines(self.name.clone(), &polylines, self.filled)
+    }
+
+    pub fn scaled(&self, scale: Vec2<f32>) -> Mesh {
+        self.map_points(|p| p * scale)
+    }
+
+    pub fn mirrored(&self, mirror_x: bool, mirror_y: bool) -> Mesh {
+        let scale = Vec2::new(if mirror_x { -1.0 } else { 1.0 }, if mirror_y { -1.0 } else { 1.0 });
+        self.scaled(scale)
+    }
+
+    pub fn translated(&self, offset: Vec2<f32>) -> Mesh {
+        self.map_points(|p| p + offset)
+    }
+
+    /// Moves the centroid to the local origin, so rotation happens around it.
+    pub fn recentered(&self) -> Mesh {
+        self.translated(-self.centroid())
+    }
+
+    /// Lines of both meshes in one mesh with the name and fill of `self`.
+    pub fn merged(&self, other: &Mesh) -> Mesh {
+        let mut points = self.points.clone();
+        points.extend_from_slice(&other.points);
+        Mesh::new(self.name.clone(), points, self.filled)
+    }
+
+    pub fn from_polylines(name: String, polylines: &[Polyline], filled: bool) -> Mesh {
+        let mut points = vec![];
+        for polyline in polylines {
+            points.extend(polyline.points.windows(2).map(|w| MeshLine::new(w[0], w[1])));
+            if polyline.closed && polyline.points.len() > 2 {
+                points.push(MeshLine::new(*polyline.points.last().unwrap(), polyline.points[0]));
+            }
+        }
+        Mesh::new(name, points, filled)
+    }
+
+    fn map_points(&self, f: impl Fn(Vec2<f32>) -> Vec2<f32>) -> Mesh {
+        let points = self.points.iter().map(|l| MeshLine::new(f(l.start), f(l.end))).collect();
+        Mesh::new(self.name.clone(), points, self.filled)
+    }
+}
+
+/// Axis aligned bounding box.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Aabb {
+    pub min: Vec2<f32>,
+    pub max: Vec2<f32>,
+}
+
+impl Aabb {
+    pub fn from_points(points: impl IntoIterator<Item = Vec2<f32>>) -> Option<Aabb> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(Aabb { min: first, max: first }, |b, p| Aabb {
+            min: Vec2::partial_min(b.min, p),
+            max: Vec2::partial_max(b.max, p),
+        }))
+    }
+
+    pub fn size(&self) -> Vec2<f32> {
+        self.max - self.min
+    }
+
+    pub fn center(&self) -> Vec2<f32> {
+        (self.min + self.max) / 2.0
+    }
+
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min.x <= other.max.x && other.min.x <= self.max.x
+            && self.min.y <= other.max.y && other.min.y <= self.max.y
+    }
+}
+
+/// Chain of connected vertices. A closed polyline also connects the last vertex to the first.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polyline {
+    pub points: Vec<Vec2<f32>>,
+    pub closed: bool,
+}
+
+/// Endpoints closer than this are treated as the same vertex.
+pub const WELD_EPSILON: f32 = 1e-3;
+
+/// Shoelace area, positive for counter-clockwise outlines in a y-up frame.
+pub fn polygon_signed_area(polygon: &[Vec2<f32>]) -> f32 {
+    let mut sum = 0.0;
+    for (i, a) in polygon.iter().enumerate() {
+        let b = polygon[(i + 1) % polygon.len()];
+        sum += a.x * b.y - b.x * a.y;
+    }
+    sum * 0.5
+}
+
+pub fn polygon_centroid(polygon: &[Vec2<f32>]) -> Vec2<f32> {
+    let area = polygon_signed_area(polygon);
+    if area.abs() <= f32::EPSILON {
+        return polygon.iter().copied().sum::<Vec2<f32>>() / polygon.len().max(1) as f32;
+    }
+
+    let mut c = Vec2::zero();
+    for (i, a) in polygon.iter().enumerate() {
+        let b = polygon[(i + 1) % polygon.len()];
+        c += (*a + b) * (a.x * b.y - b.x * a.y);
+    }
+    c / (6.0 * area)
+}
+
+/// Even-odd rule.
+pub fn point_in_polygon(p: Vec2<f32>, polygon: &[Vec2<f32>]) -> bool {
+    let mut inside = false;
+    for (i, a) in polygon.iter().enumerate() {
+        let b = polygon[(i + 1) % polygon.len()];
+        if (a.y > p.y) != (b.y > p.y) && p.x < a.x + (b.x - a.x) * (p.y - a.y) / (b.y - a.y) {
+            inside = !inside;
+        }
+    }
+    inside
 }
 
+fn douglas_peucker(points: &[Vec2<f32>], tolerance: f32) -> Vec<Vec2<f32>> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+
+    let first = points[0];
+    let last = *points.last().unwrap();
+    let (index, dist) = points[1..points.len() - 1].iter()
+        .enumerate()
+        .map(|(i, p)| (i + 1, distance_to_segment(*p, first, last)))
+        .fold((0, -1.0), |best, cur| if cur.1 > best.1 { cur } else { best });
+
+    if dist <= tolerance {
+        return vec![first, last];
+    }
+
+    let mut left = douglas_peucker(&points[..=index], tolerance);
+    let right = douglas_peucker(&points[index..], tolerance);
+    left.pop();
+    left.extend(right);
+    left
+}
+
+pub fn distance_to_segment(p: Vec2<f32>, a: Vec2<f32>, b: Vec2<f32>) -> f32 {
+    let ab = b - a;
+    let len2 = ab.magnitude_squared();
+    if len2 <= f32::EPSILON {
+        return p.distance(a);
+    }
+    let t = ((p - a).dot(ab) / len2).clamp(0.0, 1.0);
+    p.distance(a + ab * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(size: f32) -> Mesh {
+        let polyline = Polyline {
+            points: vec![Vec2::new(0.0, 0.0), Vec2::new(size, 0.0), Vec2::new(size, size), Vec2::new(0.0, size)],
+            closed: true,
+        };
+        Mesh::from_polylines("square".to_string(), &[polyline], false)
+    }
+
+    #[test]
+    fn area_centroid_and_bounds() {
+        let mesh = square(2.0);
+        assert!((mesh.area() - 4.0).abs() < 1e-5);
+        assert!(mesh.centroid().distance(Vec2::new(1.0, 1.0)) < 1e-5);
+
+        let aabb = mesh.aabb().unwrap();
+        assert_eq!(aabb.min, Vec2::new(0.0, 0.0));
+        assert_eq!(aabb.max, Vec2::new(2.0, 2.0));
+        assert!(mesh.recentered().centroid().magnitude() < 1e-5);
+    }
+
+    #[test]
+    fn welds_shuffled_segments() {
+        let mut lines = square(1.0).get_lines().clone();
+        lines.swap(0, 2);
+        let (start, end) = (lines[1].start, lines[1].end);
+        lines[1] = MeshLine::new(end, start);
+        lines.push(MeshLine::new(Vec2::new(5.0, 0.0), Vec2::new(6.0, 0.0)));
+        lines.push(MeshLine::new(Vec2::new(6.0, 0.0), Vec2::new(6.0, 1.0)));
+
+        let polylines = Mesh::new("m".to_string(), lines, false).polylines();
+        assert_eq!(polylines.len(), 2);
+        assert!(polylines.iter().any(|p| p.closed && p.points.len() == 4));
+        assert!(polylines.iter().any(|p| !p.closed && p.points.len() == 3));
+    }
+
+    #[test]
+    fn hull_skips_inner_vertices() {
+        let inner = Mesh::new("inner".to_string(), vec![MeshLine::new(Vec2::new(1.0, 1.0), Vec2::new(1.5, 0.5))], false);
+        let hull = square(2.0).merged(&inner).convex_hull();
+        assert_eq!(hull.len(), 4);
+        assert!(polygon_signed_area(&hull) > 0.0);
+    }
+
+    #[test]
+    fn simplify_drops_collinear_points() {
+        let polyline = Polyline {
+            points: (0..=10).map(|i| Vec2::new(i as f32, if i == 5 { 0.01 } else { 0.0 })).collect(),
+            closed: false,
+        };
+        let mesh = Mesh::from_polylines("line".to_string(), &[polyline], false);
+        assert_eq!(mesh.simplified(0.1).get_lines().len(), 1);
+        assert_eq!(square(1.0).simplified(0.1).get_lines().len(), 4);
+    }
+
+    #[test]
+    fn mirror_keeps_area() {
+        let mesh = square(3.0).mirrored(true, false).scaled(Vec2::new(2.0, 1.0));
+        assert!((mesh.area() - 18.0).abs() < 1e-4);
+        assert_eq!(mesh.aabb().unwrap().min, Vec2::new(-6.0, 0.0));
+    }
+}
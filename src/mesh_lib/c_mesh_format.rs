@@ -0,0 +1,130 @@
+﻿//! On-disk layouts of `.mesh` files.
+//!
+//! v1 stores every segment as a `start`/`end` pair. v2 stores each vertex once and
+//! connects them with index polylines:
+//!
+//! `{"version": 2, "name": "player", "filled": false, "vertices": [[-80, 50], ...], "polylines": [{"indices": [0, 1, 2], "closed": true}]}`
+//!
+//! Both are read, meshes are always written as v2.
+
+use serde::{Deserialize, Serialize};
+use vek::Vec2;
+use crate::mesh_lib::c_mesh::{Mesh, MeshLine, Polyline, WELD_EPSILON};
+
+pub const MESH_FORMAT_VERSION: u32 = 2;
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MeshFile {
+    V2(MeshFileV2),
+    V1(MeshFileV1),
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MeshFileV1 {
+    name: String,
+    points: Vec<MeshLine>,
+    filled: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MeshFileV2 {
+    version: u32,
+    name: String,
+    filled: bool,
+    vertices: Vec<[f32; 2]>,
+    polylines: Vec<IndexPolyline>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct IndexPolyline {
+    indices: Vec<u32>,
+    #[serde(default)]
+    closed: bool,
+}
+
+/// Fails on a v2 polyline index past the vertex list, so a broken file is rejected instead of loaded.
+impl TryFrom<MeshFile> for Mesh {
+    type Error = String;
+
+    fn try_from(file: MeshFile) -> Result<Mesh, String> {
+        match file {
+            MeshFile::V1(v1) => Ok(Mesh::new(v1.name, v1.points, v1.filled)),
+            MeshFile::V2(v2) => {
+                let vertex = |i: &u32| v2.vertices.get(*i as usize)
+                    .map(|v| Vec2::from(*v))
+                    .ok_or_else(|| format!("mesh '{}': vertex index {} out of range ({} vertices)", v2.name, i, v2.vertices.len()));
+                let polylines = v2.polylines.iter()
+                    .map(|p| Ok(Polyline { points: p.indices.iter().map(vertex).collect::<Result<_, String>>()?, closed: p.closed }))
+                    .collect::<Result<Vec<Polyline>, String>>()?;
+                Ok(Mesh::from_polylines(v2.name, &polylines, v2.filled))
+            }
+        }
+    }
+}
+
+impl From<Mesh> for MeshFile {
+    fn from(mesh: Mesh) -> MeshFile {
+        let mut vertices: Vec<Vec2<f32>> = vec![];
+        let mut index_of = |p: Vec2<f32>| -> u32 {
+            match vertices.iter().position(|v| v.distance(p) <= WELD_EPSILON) {
+                Some(i) => i as u32,
+                None => {
+                    vertices.push(p);
+                    vertices.len() as u32 - 1
+                }
+            }
+        };
+
+        let polylines = mesh.polylines().into_iter()
+            .map(|p| IndexPolyline {
+                indices: p.points.iter().map(|v| index_of(*v)).collect(),
+                closed: p.closed,
+            })
+            .collect();
+
+        MeshFile::V2(MeshFileV2 {
+            version: MESH_FORMAT_VERSION,
+            name: mesh.get_name().clone(),
+            filled: mesh.is_filled(),
+            vertices: vertices.iter().map(|v| [v.x, v.y]).collect(),
+            polylines,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V1: &str = r#"{"name":"tri","filled":false,"points":[
+        {"start":{"x":0.0,"y":0.0},"end":{"x":10.0,"y":0.0}},
+        {"start":{"x":10.0,"y":0.0},"end":{"x":0.0,"y":10.0}},
+        {"start":{"x":0.0,"y":10.0},"end":{"x":0.0,"y":0.0}}]}"#;
+
+    #[test]
+    fn reads_v1() {
+        let mesh: Mesh = serde_json::from_str(V1).unwrap();
+        assert_eq!(mesh.get_name(), "tri");
+        assert_eq!(mesh.get_lines().len(), 3);
+    }
+
+    #[test]
+    fn writes_compact_v2_and_reads_it_back() {
+        let mesh: Mesh = serde_json::from_str(V1).unwrap();
+        let json = serde_json::to_string(&mesh).unwrap();
+        assert!(json.contains("\"version\":2"));
+        assert!(json.contains("\"vertices\":[[0.0,0.0],[10.0,0.0],[0.0,10.0]]"));
+
+        let back: Mesh = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.get_lines().len(), 3);
+        assert!((back.area() - mesh.area()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn rejects_out_of_range_v2_index() {
+        let json = r#"{"version":2,"name":"bad","filled":false,"vertices":[[0.0,0.0],[10.0,0.0]],"polylines":[{"indices":[0,1,5]}]}"#;
+        let err = serde_json::from_str::<Mesh>(json).err().unwrap();
+        assert!(err.to_string().contains("vertex index 5 out of range"));
+    }
+}
@@ -0,0 +1,374 @@
+﻿//! Converts the vector outlines of an SVG document into a `Mesh`.
+//!
+//! Supported elements are `path`, `line`, `polyline`, `polygon` and `rect`. Path data may use
+//! every command, béziers are flattened and elliptical arcs become straight segments.
+//! `transform` attributes, styles and `<use>` references are ignored.
+
+use std::fmt;
+use std::path::Path;
+use vek::Vec2;
+use crate::mesh_lib::c_mesh::{Mesh, Polyline};
+
+/// Segments per flattened curve before simplification.
+const CURVE_STEPS: usize = 16;
+/// Douglas–Peucker tolerance applied to the imported outlines, in SVG user units.
+const SIMPLIFY_TOLERANCE: f32 = 0.25;
+
+#[derive(Debug)]
+pub enum SvgImportError {
+    Io(std::io::Error),
+    Parse(String),
+    Empty,
+}
+
+impl From<std::io::Error> for SvgImportError {
+    fn from(e: std::io::Error) -> Self { Self::Io(e) }
+}
+
+impl fmt::Display for SvgImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SvgImportError::Io(e) => write!(f, "{e}"),
+            SvgImportError::Parse(e) => write!(f, "bad svg: {e}"),
+            SvgImportError::Empty => write!(f, "svg has no supported outlines"),
+        }
+    }
+}
+
+/// Mesh named after the file stem, recentered on its centroid.
+pub fn import_svg_file(path: &impl AsRef<Path>) -> Result<Mesh, SvgImportError> {
+    let text = std::fs::read_to_string(path.as_ref())?;
+    let name = path.as_ref().file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    import_svg(&text, name)
+}
+
+pub fn import_svg(text: &str, name: &str) -> Result<Mesh, SvgImportError> {
+    let mut polylines = vec![];
+
+    for (tag, attrs) in elements(text)? {
+        let attr = |key: &str| attrs.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str());
+        let num = |key: &str| attr(key).and_then(|v| numbers(v).first().copied()).unwrap_or(0.0);
+
+        match tag.as_str() {
+            "path" => polylines.extend(parse_path(attr("d").unwrap_or_default())?),
+            "line" => polylines.push(Polyline {
+                points: vec![Vec2::new(num("x1"), num("y1")), Vec2::new(num("x2"), num("y2"))],
+                closed: false,
+            }),
+            "polyline" | "polygon" => {
+                let n = numbers(attr("points").unwrap_or_default());
+                let points: Vec<Vec2<f32>> = n.chunks_exact(2).map(|c| Vec2::new(c[0], c[1])).collect();
+                if points.len() >= 2 {
+                    polylines.push(Polyline { points, closed: tag == "polygon" });
+                }
+            }
+            "rect" => {
+                let (x, y, w, h) = (num("x"), num("y"), num("width"), num("height"));
+                polylines.push(Polyline {
+                    points: vec![Vec2::new(x, y), Vec2::new(x + w, y), Vec2::new(x + w, y + h), Vec2::new(x, y + h)],
+                    closed: true,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    polylines.retain(|p| p.points.len() >= 2);
+    if polylines.is_empty() {
+        return Err(SvgImportError::Empty);
+    }
+
+    Ok(Mesh::from_polylines(name.to_string(), &polylines, false)
+        .simplified(SIMPLIFY_TOLERANCE)
+        .recentered())
+}
+
+/// Tag name and `(key, value)` attribute pairs.
+type Element = (String, Vec<(String, String)>);
+
+/// Start and self-closing tags with their attributes, comments and declarations skipped.
+fn elements(text: &str) -> Result<Vec<Element>, SvgImportError> {
+    let mut result = vec![];
+    let mut rest = text;
+
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+
+        if let Some(comment) = rest.strip_prefix("!--") {
+            let end = comment.find("-->").ok_or_else(|| SvgImportError::Parse("unterminated comment".into()))?;
+            rest = &comment[end + 3..];
+            continue;
+        }
+
+        let end = rest.find('>').ok_or_else(|| SvgImportError::Parse("unterminated tag".into()))?;
+        let tag = &rest[..end];
+        rest = &rest[end + 1..];
+
+        if tag.starts_with(['/', '?', '!']) {
+            continue;
+        }
+
+        let tag = tag.trim_end_matches('/');
+        let name_end = tag.find(|c: char| c.is_whitespace()).unwrap_or(tag.len());
+        result.push((tag[..name_end].to_string(), attributes(&tag[name_end..])));
+    }
+    Ok(result)
+}
+
+fn attributes(text: &str) -> Vec<(String, String)> {
+    let mut attrs = vec![];
+    let mut rest = text;
+
+    while let Some(eq) = rest.find('=') {
+        let key = rest[..eq].trim().to_string();
+        let after = rest[eq + 1..].trim_start();
+        let Some(quote) = after.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            break;
+        };
+
+        let value = &after[1..];
+        let Some(close) = value.find(quote) else {
+            break;
+        };
+        attrs.push((key, value[..close].to_string()));
+        rest = &value[close + 1..];
+    }
+    attrs
+}
+
+/// All numbers in an attribute, accepting `1-2`, `.5.5` and exponents without separators.
+fn numbers(text: &str) -> Vec<f32> {
+    let mut lexer = PathLexer { text: text.as_bytes(), pos: 0 };
+    let mut result = vec![];
+    while let Some(token) = lexer.next_token() {
+        if let PathToken::Number(n) = token {
+            result.push(n);
+        }
+    }
+    result
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum PathToken {
+    Command(u8),
+    Number(f32),
+}
+
+struct PathLexer<'a> {
+    text: &'a [u8],
+    pos: usize,
+}
+
+impl PathLexer<'_> {
+    fn next_token(&mut self) -> Option<PathToken> {
+        while self.pos < self.text.len() && (self.text[self.pos].is_ascii_whitespace() || self.text[self.pos] == b',') {
+            self.pos += 1;
+        }
+        let c = *self.text.get(self.pos)?;
+
+        if c.is_ascii_alphabetic() && c != b'e' && c != b'E' {
+            self.pos += 1;
+            return Some(PathToken::Command(c));
+        }
+
+        let start = self.pos;
+        let mut seen_dot = false;
+        let mut seen_exp = false;
+        if matches!(c, b'+' | b'-') {
+            self.pos += 1;
+        }
+        while let Some(&c) = self.text.get(self.pos) {
+            match c {
+                b'0'..=b'9' => {}
+                b'.' if !seen_dot && !seen_exp => seen_dot = true,
+                b'e' | b'E' if !seen_exp => {
+                    seen_exp = true;
+                    if matches!(self.text.get(self.pos + 1), Some(b'+' | b'-')) {
+                        self.pos += 1;
+                    }
+                }
+                _ => break,
+            }
+            self.pos += 1;
+        }
+
+        if self.pos == start {
+            // unknown character, skip it
+            self.pos += 1;
+            return self.next_token();
+        }
+
+        let number = std::str::from_utf8(&self.text[start..self.pos]).ok()?.parse().unwrap_or(0.0);
+        Some(PathToken::Number(number))
+    }
+}
+
+fn parse_path(d: &str) -> Result<Vec<Polyline>, SvgImportError> {
+    let mut lexer = PathLexer { text: d.as_bytes(), pos: 0 };
+    let mut tokens = vec![];
+    while let Some(t) = lexer.next_token() {
+        tokens.push(t);
+    }
+
+    let mut polylines = vec![];
+    let mut current: Vec<Vec2<f32>> = vec![];
+    let mut pen = Vec2::zero();
+    let mut start = Vec2::zero();
+    // reflected control point for S/T
+    let mut last_control: Option<(u8, Vec2<f32>)> = None;
+    let mut command = 0u8;
+    let mut i = 0;
+
+    let finish = |current: &mut Vec<Vec2<f32>>, polylines: &mut Vec<Polyline>, closed: bool| {
+        // an explicit segment back to the start is already implied by `closed`
+        if closed && current.len() > 2 && current[0].distance(*current.last().unwrap()) < 1e-4 {
+            current.pop();
+        }
+        if current.len() >= 2 {
+            polylines.push(Polyline { points: std::mem::take(current), closed });
+        }
+        current.clear();
+    };
+
+    while i < tokens.len() {
+        if let PathToken::Command(c) = tokens[i] {
+            command = c;
+            i += 1;
+        } else if command == 0 {
+            return Err(SvgImportError::Parse("path data must start with a command".into()));
+        }
+
+        let relative = command.is_ascii_lowercase();
+        let base = if relative { pen } else { Vec2::zero() };
+        let arg_count = match command.to_ascii_uppercase() {
+            b'M' | b'L' | b'T' => 2,
+            b'H' | b'V' => 1,
+            b'S' | b'Q' => 4,
+            b'C' => 6,
+            b'A' => 7,
+            b'Z' => 0,
+            other => return Err(SvgImportError::Parse(format!("unknown path command {}", other as char))),
+        };
+
+        let mut args = [0.0f32; 7];
+        for (k, arg) in args.iter_mut().enumerate().take(arg_count) {
+            match tokens.get(i + k) {
+                Some(PathToken::Number(n)) => *arg = *n,
+                _ => return Err(SvgImportError::Parse(format!("missing arguments for {}", command as char))),
+            }
+        }
+        i += arg_count;
+        let point = |x: usize| base + Vec2::new(args[x], args[x + 1]);
+
+        match command.to_ascii_uppercase() {
+            b'M' => {
+                finish(&mut current, &mut polylines, false);
+                pen = point(0);
+                start = pen;
+                current.push(pen);
+                // further coordinate pairs are implicit line-tos
+                command = if relative { b'l' } else { b'L' };
+            }
+            b'L' => pen = point(0),
+            b'H' => pen = Vec2::new(args[0] + if relative { pen.x } else { 0.0 }, pen.y),
+            b'V' => pen = Vec2::new(pen.x, args[0] + if relative { pen.y } else { 0.0 }),
+            b'C' | b'S' => {
+                let (c1, c2, end) = if command.eq_ignore_ascii_case(&b'C') {
+                    (point(0), point(2), point(4))
+                } else {
+                    let c1 = match last_control {
+                        Some((b'C', c)) => pen * 2.0 - c,
+                        _ => pen,
+                    };
+                    (c1, point(0), point(2))
+                };
+                current.extend((1..=CURVE_STEPS).map(|s| cubic(pen, c1, c2, end, s as f32 / CURVE_STEPS as f32)));
+                pen = end;
+                last_control = Some((b'C', c2));
+            }
+            b'Q' | b'T' => {
+                let (c, end) = if command.eq_ignore_ascii_case(&b'Q') {
+                    (point(0), point(2))
+                } else {
+                    let c = match last_control {
+                        Some((b'Q', c)) => pen * 2.0 - c,
+                        _ => pen,
+                    };
+                    (c, point(0))
+                };
+                current.extend((1..=CURVE_STEPS).map(|s| quadratic(pen, c, end, s as f32 / CURVE_STEPS as f32)));
+                pen = end;
+                last_control = Some((b'Q', c));
+            }
+            b'A' => pen = point(5),
+            b'Z' => {
+                finish(&mut current, &mut polylines, true);
+                pen = start;
+                current.push(pen);
+            }
+            _ => {}
+        }
+
+        if matches!(command.to_ascii_uppercase(), b'L' | b'H' | b'V' | b'A') {
+            current.push(pen);
+        }
+        if !matches!(command.to_ascii_uppercase(), b'C' | b'S' | b'Q' | b'T') {
+            last_control = None;
+        }
+    }
+
+    finish(&mut current, &mut polylines, false);
+    Ok(polylines)
+}
+
+fn cubic(p0: Vec2<f32>, p1: Vec2<f32>, p2: Vec2<f32>, p3: Vec2<f32>, t: f32) -> Vec2<f32> {
+    let u = 1.0 - t;
+    p0 * (u * u * u) + p1 * (3.0 * u * u * t) + p2 * (3.0 * u * t * t) + p3 * (t * t * t)
+}
+
+fn quadratic(p0: Vec2<f32>, p1: Vec2<f32>, p2: Vec2<f32>, t: f32) -> Vec2<f32> {
+    let u = 1.0 - t;
+    p0 * (u * u) + p1 * (2.0 * u * t) + p2 * (t * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn imports_lines_and_shapes() {
+        let svg = r#"<?xml version="1.0"?>
+            <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 100">
+              <!-- <line x1="0" y1="0" x2="1" y2="1"/> -->
+              <polygon points="0,0 40,0 40,40 0,40"/>
+              <line x1='60' y1='0' x2='100' y2='0'/>
+              <path d="M60 20h40v20H60z"/>
+            </svg>"#;
+        let mesh = import_svg(svg, "shapes").unwrap();
+
+        assert_eq!(mesh.get_name(), "shapes");
+        assert_eq!(mesh.get_lines().len(), 4 + 1 + 4);
+        assert!((mesh.area() - (1600.0 + 800.0)).abs() < 1e-2);
+    }
+
+    #[test]
+    fn flattens_curves_with_compact_numbers() {
+        let svg = r#"<svg><path d="m0,0c0-50,100-50,100,0s-100,50-100,0z"/></svg>"#;
+        let mesh = import_svg(svg, "curve").unwrap();
+
+        let polylines = mesh.polylines();
+        assert_eq!(polylines.len(), 1);
+        assert!(polylines[0].closed);
+        assert!(polylines[0].points.len() > 8);
+
+        let aabb = mesh.aabb().unwrap();
+        assert!((aabb.size().x - 100.0).abs() < 0.5);
+        assert!((aabb.size().y - 75.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn rejects_empty_documents() {
+        assert!(matches!(import_svg("<svg><circle r='5'/></svg>", "none"), Err(SvgImportError::Empty)));
+        assert!(matches!(import_svg("<svg><path d='10 10'/></svg>", "bad"), Err(SvgImportError::Parse(_))));
+    }
+}
@@ -1 +1,5 @@
-﻿pub mod c_mesh;
\ No newline at end of file
+﻿pub mod c_mesh;
+pub mod c_asteroid_generator;
+pub mod c_fracture;
+pub mod c_mesh_format;
+pub mod c_svg_import;
\ No newline at end of file
@@ -0,0 +1,107 @@
+﻿use std::cell::RefCell;
+use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use crate::classes::c_input::PlayerInput;
+use crate::classes::e_action::{Action, Axis};
+use crate::classes::t_input_backend::InputBackend;
+
+/// One player's input for one simulation tick, what lockstep peers exchange. Actions are bit
+/// sets in `Action::ALL` order, axes are quantized so both peers simulate the exact same values.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InputFrame {
+    #[serde(rename = "d")]
+    down: u16,
+    #[serde(rename = "p")]
+    pressed: u16,
+    #[serde(rename = "u")]
+    released: u16,
+    #[serde(rename = "r")]
+    rotate: i8,
+    #[serde(rename = "t")]
+    thrust: i8,
+}
+
+fn bit(action: Action) -> u16 {
+    1 << Action::ALL.iter().position(|a| *a == action).unwrap_or(0)
+}
+
+fn bits(mut f: impl FnMut(Action) -> bool) -> u16 {
+    Action::ALL.into_iter().filter(|a| f(*a)).fold(0, |acc, a| acc | bit(a))
+}
+
+fn quantize(value: f32) -> i8 {
+    (value.clamp(-1.0, 1.0) * 127.0).round() as i8
+}
+
+impl InputFrame {
+    pub fn sample(player: &PlayerInput) -> Self {
+        Self {
+            down: bits(|a| player.is_action_down(a)),
+            pressed: bits(|a| player.is_action_pressed(a)),
+            released: bits(|a| player.is_action_released(a)),
+            rotate: quantize(player.get_steering(Axis::Rotate)),
+            thrust: quantize(player.get_steering(Axis::Thrust)),
+        }
+    }
+
+    /// Adds the edges of a frame that produced no tick, so a tap during a stall is not lost.
+    pub fn merge_edges(&mut self, earlier: &InputFrame) {
+        self.pressed |= earlier.pressed;
+        self.released |= earlier.released;
+    }
+
+    /// Same held actions and axes, no edges; repeats the input for extra ticks of one frame.
+    pub fn without_edges(&self) -> Self {
+        Self { pressed: 0, released: 0, ..*self }
+    }
+
+    pub fn is_down(&self, action: Action) -> bool {
+        self.down & bit(action) != 0
+    }
+
+    pub fn is_pressed(&self, action: Action) -> bool {
+        self.pressed & bit(action) != 0
+    }
+
+    pub fn is_released(&self, action: Action) -> bool {
+        self.released & bit(action) != 0
+    }
+
+    pub fn get_axis(&self, axis: Axis) -> f32 {
+        match axis {
+            Axis::Rotate => self.rotate as f32 / 127.0,
+            Axis::Thrust => self.thrust as f32 / 127.0,
+        }
+    }
+}
+
+/// Replays the frame of the current tick into the simulation `Input`. Cloning gives another
+/// handle to the same frame, like `VirtualDevice`.
+#[derive(Clone, Default)]
+pub struct FrameBackend {
+    frame: Rc<RefCell<InputFrame>>,
+}
+
+impl FrameBackend {
+    pub fn set(&self, frame: InputFrame) {
+        *self.frame.borrow_mut() = frame;
+    }
+}
+
+impl InputBackend for FrameBackend {
+    fn is_action_down(&self, action: Action) -> bool {
+        self.frame.borrow().is_down(action)
+    }
+
+    fn is_action_pressed(&self, action: Action) -> bool {
+        self.frame.borrow().is_pressed(action)
+    }
+
+    fn is_action_released(&self, action: Action) -> bool {
+        self.frame.borrow().is_released(action)
+    }
+
+    fn get_axis(&self, axis: Axis) -> f32 {
+        self.frame.borrow().get_axis(axis)
+    }
+}
@@ -0,0 +1,192 @@
+﻿use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use rand::Rng;
+use vek::Vec2;
+use crate::classes::e_game_mode::GameMode;
+use crate::net_lib::c_lockstep::LockstepSession;
+use crate::net_lib::e_net_message::{NetMessage, PROTOCOL_VERSION};
+
+/// Seconds between the joiner's hellos.
+const HELLO_INTERVAL: f32 = 0.5;
+/// Seconds a joiner waits for the host before giving up.
+const JOIN_TIMEOUT: f32 = 10.0;
+
+enum Role {
+    Host { port: u16, mode: GameMode },
+    Join { host: SocketAddr },
+}
+
+/// Handshake before a lockstep session: the host waits on a port, the joiner greets it until it
+/// answers with the session settings. Poll every frame until `take_session` returns the session.
+pub struct Lobby {
+    socket: Option<UdpSocket>,
+    role: Role,
+    playfield: Vec2<usize>,
+    timer: f32,
+    waited: f32,
+    session: Option<LockstepSession>,
+    error: Option<String>,
+}
+
+impl Lobby {
+    pub fn host(port: u16, mode: GameMode, playfield: Vec2<usize>) -> io::Result<Self> {
+        let socket = UdpSocket::bind(("0.0.0.0", port))?;
+        socket.set_nonblocking(true)?;
+        Ok(Self::new(socket, Role::Host { port, mode }, playfield))
+    }
+
+    pub fn join(address: &str, playfield: Vec2<usize>) -> io::Result<Self> {
+        let host = address.to_socket_addrs()?
+            .find(|a| a.is_ipv4())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no IPv4 address"))?;
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        socket.set_nonblocking(true)?;
+        Ok(Self::new(socket, Role::Join { host }, playfield))
+    }
+
+    fn new(socket: UdpSocket, role: Role, playfield: Vec2<usize>) -> Self {
+        Self { socket: Some(socket), role, playfield, timer: 0.0, waited: 0.0, session: None, error: None }
+    }
+
+    pub fn get_error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Status line for the menu.
+    pub fn describe(&self) -> String {
+        if let Some(error) = &self.error {
+            return error.clone();
+        }
+        if self.session.is_some() {
+            return String::from("Connected");
+        }
+        match &self.role {
+            Role::Host { port, .. } => format!("Waiting for a player on port {port}"),
+            Role::Join { host } => format!("Connecting to {host}"),
+        }
+    }
+
+    pub fn take_session(&mut self) -> Option<LockstepSession> {
+        self.session.take()
+    }
+
+    pub fn poll(&mut self, dt: f32) {
+        if self.error.is_some() || self.session.is_some() {
+            return;
+        }
+        let Some(socket) = &self.socket else {
+            return;
+        };
+
+        if let Role::Join { host } = self.role {
+            self.timer -= dt;
+            self.waited += dt;
+            if self.timer <= 0.0 {
+                self.timer = HELLO_INTERVAL;
+                let hello = NetMessage::Hello { version: PROTOCOL_VERSION, playfield: self.playfield.into_tuple() };
+                if let Err(e) = socket.send_to(&hello.encode(), host) {
+                    self.error = Some(format!("Cannot reach {host}: {e}"));
+                    return;
+                }
+            }
+            if self.waited > JOIN_TIMEOUT {
+                self.error = Some(format!("No answer from {host}"));
+                return;
+            }
+        }
+
+        let mut buffer = [0u8; 2048];
+        while let Some(socket) = &self.socket
+            && let Ok((len, from)) = socket.recv_from(&mut buffer)
+        {
+            if let Some(message) = NetMessage::decode(&buffer[..len]) {
+                self.receive(message, from);
+            }
+        }
+    }
+
+    fn receive(&mut self, message: NetMessage, from: SocketAddr) {
+        match (&self.role, message) {
+            (Role::Host { mode, .. }, NetMessage::Hello { version, playfield }) => {
+                let mode = *mode;
+                let reject = if version != PROTOCOL_VERSION {
+                    Some(format!("Version {version} does not match the host's {PROTOCOL_VERSION}"))
+                } else if playfield != self.playfield.into_tuple() {
+                    Some(format!("Playfield {}x{} does not match the host's {}x{}", playfield.0, playfield.1, self.playfield.x, self.playfield.y))
+                } else {
+                    None
+                };
+
+                let Some(socket) = self.socket.take() else {
+                    return;
+                };
+                if let Some(reason) = reject {
+                    let _ = socket.send_to(&NetMessage::Reject { reason }.encode(), from);
+                    self.socket = Some(socket);
+                    return;
+                }
+
+                let welcome = NetMessage::Welcome { seed: rand::rng().random(), mode, player: 1 };
+                let _ = socket.send_to(&welcome.encode(), from);
+                let NetMessage::Welcome { seed, .. } = welcome else {
+                    return;
+                };
+                self.session = Some(LockstepSession::new(socket, from, seed, mode, 0, Some(welcome)));
+            }
+            (Role::Join { host }, NetMessage::Welcome { seed, mode, player }) if from == *host => {
+                if let Some(socket) = self.socket.take() {
+                    self.session = Some(LockstepSession::new(socket, from, seed, mode, player, None));
+                }
+            }
+            (Role::Join { host }, NetMessage::Reject { reason }) if from == *host => {
+                self.error = Some(reason);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn joiner_gets_the_host_settings() {
+        let mut host = Lobby::host(0, GameMode::Versus { players: 2 }, Vec2::new(800, 600)).unwrap();
+        let port = host.socket.as_ref().unwrap().local_addr().unwrap().port();
+        let mut join = Lobby::join(&format!("127.0.0.1:{port}"), Vec2::new(800, 600)).unwrap();
+
+        for _ in 0..200 {
+            host.poll(0.01);
+            join.poll(0.01);
+            if host.session.is_some() && join.session.is_some() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+
+        let (a, b) = (host.take_session().unwrap(), join.take_session().unwrap());
+        assert_eq!(a.get_seed(), b.get_seed());
+        assert_eq!(b.get_mode(), GameMode::Versus { players: 2 });
+        assert_eq!((a.get_local_player(), b.get_local_player()), (0, 1));
+    }
+
+    #[test]
+    fn mismatched_playfield_is_rejected() {
+        let mut host = Lobby::host(0, GameMode::Coop, Vec2::new(800, 600)).unwrap();
+        let port = host.socket.as_ref().unwrap().local_addr().unwrap().port();
+        let mut join = Lobby::join(&format!("127.0.0.1:{port}"), Vec2::new(640, 480)).unwrap();
+
+        for _ in 0..200 {
+            host.poll(0.01);
+            join.poll(0.01);
+            if join.get_error().is_some() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+
+        assert!(join.get_error().unwrap().starts_with("Playfield"));
+        assert!(host.take_session().is_none());
+    }
+}
@@ -0,0 +1,329 @@
+﻿use std::collections::BTreeMap;
+use std::net::{SocketAddr, UdpSocket};
+use crate::classes::e_game_mode::GameMode;
+use crate::net_lib::c_input_frame::InputFrame;
+use crate::net_lib::e_net_message::NetMessage;
+
+/// Fixed simulation step of a lockstep session, both peers must use the same one.
+pub const TICK: f32 = 1.0 / 60.0;
+/// Local input is scheduled this many ticks ahead, which hides up to that much latency.
+pub const INPUT_DELAY: u32 = 3;
+
+const MAX_FRAMES_PER_PACKET: usize = 64;
+const CHECKSUMS_PER_PACKET: usize = 16;
+/// Seconds without a datagram before the peer counts as gone.
+const TIMEOUT: f32 = 5.0;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SessionState {
+    Running,
+    /// State checksums of this tick differ between the peers.
+    Desync(u32),
+    Disconnected(String),
+}
+
+/// One side of a two-player lockstep game over UDP. Every tick each peer contributes one
+/// `InputFrame`; a tick is simulated only once both frames are known, so both simulations see
+/// the same input. Frames are resent until acknowledged and recent state checksums ride along
+/// to catch desyncs.
+pub struct LockstepSession {
+    socket: UdpSocket,
+    remote: SocketAddr,
+    seed: u64,
+    mode: GameMode,
+    local_player: usize,
+    /// Host only, resent when the joiner repeats its hello because the first one got lost.
+    welcome: Option<NetMessage>,
+
+    local_frames: BTreeMap<u32, InputFrame>,
+    next_local: u32,
+    remote_frames: BTreeMap<u32, InputFrame>,
+    /// Remote frames are complete below this tick.
+    remote_complete: u32,
+    /// The peer has all of our frames below this tick.
+    acked: u32,
+    /// Next tick to simulate, older frames are no longer needed locally.
+    consumed: u32,
+
+    /// Local checksums the peer has not acknowledged yet.
+    unacked_checksums: BTreeMap<u32, u64>,
+    /// The peer's checksums are complete below this tick.
+    checksums_received: u32,
+    /// Checksums waiting for the other side's of the same tick.
+    local_checksums: BTreeMap<u32, u64>,
+    remote_checksums: BTreeMap<u32, u64>,
+    verified: u32,
+
+    state: SessionState,
+    silence: f32,
+}
+
+impl LockstepSession {
+    pub fn new(socket: UdpSocket, remote: SocketAddr, seed: u64, mode: GameMode, local_player: usize, welcome: Option<NetMessage>) -> Self {
+        if let Err(e) = socket.set_nonblocking(true) {
+            eprintln!("lockstep socket: {e}");
+        }
+
+        // the first ticks run before any input can arrive, both peers start them empty
+        let empty: BTreeMap<u32, InputFrame> = (0..INPUT_DELAY).map(|t| (t, InputFrame::default())).collect();
+
+        Self {
+            socket,
+            remote,
+            seed,
+            mode,
+            local_player,
+            welcome,
+            local_frames: empty.clone(),
+            next_local: INPUT_DELAY,
+            remote_frames: empty,
+            remote_complete: INPUT_DELAY,
+            acked: INPUT_DELAY,
+            consumed: 0,
+            unacked_checksums: BTreeMap::new(),
+            checksums_received: 0,
+            local_checksums: BTreeMap::new(),
+            remote_checksums: BTreeMap::new(),
+            verified: 0,
+            state: SessionState::Running,
+            silence: 0.0,
+        }
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn get_mode(&self) -> GameMode {
+        self.mode
+    }
+
+    pub fn get_local_player(&self) -> usize {
+        self.local_player
+    }
+
+    pub fn get_state(&self) -> &SessionState {
+        &self.state
+    }
+
+    /// Ticks whose checksums matched on both peers.
+    pub fn get_verified(&self) -> u32 {
+        self.verified
+    }
+
+    /// `true` while the local input for `tick + INPUT_DELAY` is still missing.
+    pub fn needs_local(&self, tick: u32) -> bool {
+        self.next_local <= tick + INPUT_DELAY
+    }
+
+    pub fn add_local(&mut self, frame: InputFrame) {
+        self.local_frames.insert(self.next_local, frame);
+        self.next_local += 1;
+    }
+
+    /// Frames of both players for `tick` in player order, `None` while the peer's is missing.
+    pub fn take_frames(&mut self, tick: u32) -> Option<[InputFrame; 2]> {
+        let local = *self.local_frames.get(&tick)?;
+        let remote = self.remote_frames.remove(&tick)?;
+        self.consumed = self.consumed.max(tick + 1);
+
+        Some(if self.local_player == 0 { [local, remote] } else { [remote, local] })
+    }
+
+    /// State hash after simulating `tick`, compared with the peer's once both are known.
+    pub fn record_checksum(&mut self, tick: u32, checksum: u64) {
+        self.local_checksums.insert(tick, checksum);
+        self.unacked_checksums.insert(tick, checksum);
+        self.compare_checksums();
+    }
+
+    fn compare_checksums(&mut self) {
+        let common: Vec<u32> = self.local_checksums.keys()
+            .filter(|t| self.remote_checksums.contains_key(t))
+            .copied()
+            .collect();
+
+        for tick in common {
+            let local = self.local_checksums.remove(&tick);
+            let remote = self.remote_checksums.remove(&tick);
+            if local != remote {
+                if self.state == SessionState::Running {
+                    self.state = SessionState::Desync(tick);
+                }
+                return;
+            }
+            self.verified += 1;
+        }
+    }
+
+    /// Reads every pending datagram.
+    pub fn poll(&mut self, dt: f32) {
+        self.silence += dt;
+
+        let mut buffer = [0u8; 65536];
+        while let Ok((len, from)) = self.socket.recv_from(&mut buffer) {
+            if from != self.remote {
+                continue;
+            }
+            let Some(message) = NetMessage::decode(&buffer[..len]) else {
+                continue;
+            };
+            self.silence = 0.0;
+
+            match message {
+                NetMessage::Hello { .. } => {
+                    if let Some(welcome) = self.welcome.clone() {
+                        self.send(&welcome);
+                    }
+                }
+                NetMessage::Inputs { first, frames, ack, checksums, checksum_ack } => {
+                    self.receive_inputs(first, frames, ack);
+                    self.receive_checksums(checksums, checksum_ack);
+                }
+                NetMessage::Bye => {
+                    self.state = SessionState::Disconnected(String::from("the other player left"));
+                }
+                _ => {}
+            }
+        }
+
+        if self.silence > TIMEOUT && self.state == SessionState::Running {
+            self.state = SessionState::Disconnected(String::from("connection timed out"));
+        }
+    }
+
+    fn receive_inputs(&mut self, first: u32, frames: Vec<InputFrame>, ack: u32) {
+        for (i, frame) in frames.into_iter().enumerate() {
+            let tick = first + i as u32;
+            if tick >= self.remote_complete.min(self.consumed.max(INPUT_DELAY)) {
+                self.remote_frames.entry(tick).or_insert(frame);
+            }
+        }
+        while self.remote_frames.contains_key(&self.remote_complete) {
+            self.remote_complete += 1;
+        }
+
+        self.acked = self.acked.max(ack);
+        let keep_from = self.acked.min(self.consumed);
+        self.local_frames.retain(|t, _| *t >= keep_from);
+    }
+
+    fn receive_checksums(&mut self, checksums: Vec<(u32, u64)>, checksum_ack: u32) {
+        self.unacked_checksums.retain(|t, _| *t >= checksum_ack);
+
+        // the peer sends them in order from our ack on, repeats of older ticks are skipped
+        for (tick, checksum) in checksums {
+            if tick == self.checksums_received {
+                self.remote_checksums.insert(tick, checksum);
+                self.checksums_received += 1;
+            }
+        }
+        self.compare_checksums();
+    }
+
+    /// Sends every unacknowledged local frame and the oldest unacknowledged checksums, once per frame.
+    pub fn flush(&mut self) {
+        let frames: Vec<InputFrame> = self.local_frames.range(self.acked..)
+            .take(MAX_FRAMES_PER_PACKET)
+            .map(|(_, f)| *f)
+            .collect();
+        let checksums = self.unacked_checksums.iter()
+            .take(CHECKSUMS_PER_PACKET)
+            .map(|(t, c)| (*t, *c))
+            .collect();
+
+        self.send(&NetMessage::Inputs { first: self.acked, frames, ack: self.remote_complete, checksums, checksum_ack: self.checksums_received });
+    }
+
+    /// `true` once the peer has every local frame and every local checksum was compared,
+    /// a finished peer lingers until then so the other one can complete too.
+    pub fn is_settled(&self) -> bool {
+        self.acked >= self.next_local && self.unacked_checksums.is_empty() && self.local_checksums.is_empty()
+    }
+
+    pub fn close(&mut self) {
+        self.send(&NetMessage::Bye);
+        if self.state == SessionState::Running {
+            self.state = SessionState::Disconnected(String::from("left the session"));
+        }
+    }
+
+    fn send(&self, message: &NetMessage) {
+        if let Err(e) = self.socket.send_to(&message.encode(), self.remote) {
+            eprintln!("lockstep send: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classes::e_action::Action;
+
+    fn pair() -> (LockstepSession, LockstepSession) {
+        let a = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let b = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let (addr_a, addr_b) = (a.local_addr().unwrap(), b.local_addr().unwrap());
+        (
+            LockstepSession::new(a, addr_b, 7, GameMode::Coop, 0, None),
+            LockstepSession::new(b, addr_a, 7, GameMode::Coop, 1, None),
+        )
+    }
+
+    /// Advances both peers until each simulated `ticks` ticks; the toy state is a running sum of
+    /// the frames, `tamper` changes one peer's checksum at that tick.
+    fn run(peers: &mut [LockstepSession; 2], ticks: u32, tamper: Option<u32>) -> [Vec<[InputFrame; 2]>; 2] {
+        let mut seen: [Vec<[InputFrame; 2]>; 2] = [vec![], vec![]];
+        let mut state = [0u64; 2];
+
+        for _ in 0..ticks * 20 {
+            for (p, peer) in peers.iter_mut().enumerate() {
+                peer.poll(0.001);
+                let tick = seen[p].len() as u32;
+                if tick < ticks && peer.needs_local(tick) {
+                    let mut player = crate::classes::c_input::Input::default();
+                    if (peer.next_local + p as u32).is_multiple_of(3) {
+                        player.on_key(winit::keyboard::KeyCode::Space, true);
+                    }
+                    player.update(TICK);
+                    peer.add_local(InputFrame::sample(player.player(0)));
+                }
+                if tick < ticks && let Some(frames) = peer.take_frames(tick) {
+                    state[p] = state[p].wrapping_mul(31) + frames.iter().filter(|f| f.is_down(Action::Fire)).count() as u64;
+                    let checksum = if p == 1 && tamper == Some(tick) { state[p] + 1 } else { state[p] };
+                    peer.record_checksum(tick, checksum);
+                    seen[p].push(frames);
+                }
+                peer.flush();
+            }
+            std::thread::sleep(std::time::Duration::from_micros(200));
+            if seen.iter().all(|s| s.len() as u32 >= ticks) && peers.iter().all(|p| p.is_settled()) {
+                break;
+            }
+        }
+        seen
+    }
+
+    #[test]
+    fn peers_see_the_same_frames() {
+        let (a, b) = pair();
+        let mut peers = [a, b];
+        let seen = run(&mut peers, 40, None);
+
+        assert_eq!(seen[0].len(), 40);
+        assert_eq!(seen[0], seen[1]);
+        assert!(seen[0][INPUT_DELAY as usize][0].is_down(Action::Fire));
+        assert!(seen[1][INPUT_DELAY as usize + 2][1].is_down(Action::Fire));
+        assert!(peers.iter().all(|p| *p.get_state() == SessionState::Running));
+        assert!(peers[0].get_verified() > 30);
+    }
+
+    #[test]
+    fn checksum_mismatch_reports_desync() {
+        let (a, b) = pair();
+        let mut peers = [a, b];
+        run(&mut peers, 20, Some(12));
+
+        assert!(peers.iter().any(|p| *p.get_state() == SessionState::Desync(12)));
+    }
+}
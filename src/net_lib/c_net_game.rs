@@ -0,0 +1,109 @@
+﻿use crate::assetsdb_lib::c_assets_db::AssetsDB;
+use crate::classes::c_input::{Input, PlayerInput};
+use crate::config_lib::c_config::Config;
+use crate::net_lib::c_input_frame::{FrameBackend, InputFrame};
+use crate::net_lib::c_lockstep::{LockstepSession, SessionState, TICK};
+use crate::random_lib::c_sim_rng::seed_sim_rng;
+use crate::scenes_lib::e_scene_switch::SceneSwitch;
+use crate::scenes_lib::t_scene::Scene;
+
+/// Ticks simulated at most per frame when catching up after a stall.
+const MAX_CATCH_UP: u32 = 8;
+
+/// Drives a scene from a lockstep session. The local player's input goes out as frames and the
+/// scene advances one fixed tick whenever the frames of both players are in, reading them from
+/// its own `Input` so both peers simulate the same thing.
+pub struct NetGame {
+    session: LockstepSession,
+    input: Input,
+    devices: [FrameBackend; 2],
+    tick: u32,
+    /// The scene stops at this tick, both headless peers end on the same state.
+    tick_limit: Option<u32>,
+    accumulator: f32,
+    /// Local input sampled since the last frame went out, edges included.
+    pending: Option<InputFrame>,
+    held: InputFrame,
+}
+
+impl NetGame {
+    /// Seeds the simulation random numbers, create the scene afterwards.
+    pub fn new(session: LockstepSession) -> Self {
+        seed_sim_rng(session.get_seed());
+
+        let devices = [FrameBackend::default(), FrameBackend::default()];
+        let mut input = Input::default();
+        for (player, device) in devices.iter().enumerate() {
+            input.add_player_backend(player, Box::new(device.clone()));
+        }
+
+        Self { session, input, devices, tick: 0, tick_limit: None, accumulator: 0.0, pending: None, held: InputFrame::default() }
+    }
+
+    pub fn get_session(&self) -> &LockstepSession {
+        &self.session
+    }
+
+    pub fn get_session_mut(&mut self) -> &mut LockstepSession {
+        &mut self.session
+    }
+
+    /// Ticks simulated so far.
+    pub fn get_tick(&self) -> u32 {
+        self.tick
+    }
+
+    pub fn set_tick_limit(&mut self, limit: Option<u32>) {
+        self.tick_limit = limit;
+    }
+
+    /// Samples the local player, exchanges frames and runs the ticks that are ready.
+    pub fn update(&mut self, dt: f32, local: &PlayerInput, scene: &mut dyn Scene, config: &Config, assets_db: &AssetsDB) -> SceneSwitch {
+        self.session.poll(dt);
+
+        let mut frame = InputFrame::sample(local);
+        if let Some(pending) = &self.pending {
+            frame.merge_edges(pending);
+        }
+        self.pending = Some(frame);
+        self.held = frame.without_edges();
+
+        self.accumulator = (self.accumulator + dt).min(TICK * MAX_CATCH_UP as f32);
+        let mut scene_switch = SceneSwitch::None;
+        while self.accumulator >= TICK && let Some(switch) = self.step(scene, config, assets_db) {
+            self.accumulator -= TICK;
+            if !matches!(switch, SceneSwitch::None) {
+                scene_switch = switch;
+                break;
+            }
+        }
+
+        self.session.flush();
+        scene_switch
+    }
+
+    /// Runs one tick, `None` while the peer's frame is missing.
+    fn step(&mut self, scene: &mut dyn Scene, config: &Config, assets_db: &AssetsDB) -> Option<SceneSwitch> {
+        if *self.session.get_state() != SessionState::Running || self.tick_limit.is_some_and(|l| self.tick >= l) {
+            return None;
+        }
+
+        while self.session.needs_local(self.tick) {
+            let frame = self.pending.take().unwrap_or(self.held);
+            self.session.add_local(frame);
+        }
+
+        let frames = self.session.take_frames(self.tick)?;
+        for (device, frame) in self.devices.iter().zip(frames) {
+            device.set(frame);
+        }
+
+        self.input.update(TICK);
+        let scene_switch = scene.update(TICK, &self.input, config, assets_db);
+        self.input.end_frame();
+
+        self.session.record_checksum(self.tick, scene.state_checksum());
+        self.tick += 1;
+        Some(scene_switch)
+    }
+}
@@ -0,0 +1,31 @@
+﻿use serde::{Deserialize, Serialize};
+use crate::classes::e_game_mode::GameMode;
+use crate::net_lib::c_input_frame::InputFrame;
+
+/// Bumped whenever the simulation or the messages change, peers of different versions would desync.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Datagrams between two lockstep peers, JSON encoded.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum NetMessage {
+    /// Joiner to host, repeated until a `Welcome` arrives.
+    Hello { version: u32, playfield: (usize, usize) },
+    /// Host to joiner: session settings, the joiner plays `player`.
+    Welcome { seed: u64, mode: GameMode, player: usize },
+    Reject { reason: String },
+    /// Sender's frames from tick `first` on, everything not yet acknowledged. `ack` is the first
+    /// tick the sender is still missing from the receiver. `checksums` are the oldest unacknowledged
+    /// `(tick, state hash)` pairs, `checksum_ack` the first tick whose checksum the sender lacks.
+    Inputs { first: u32, frames: Vec<InputFrame>, ack: u32, checksums: Vec<(u32, u64)>, checksum_ack: u32 },
+    Bye,
+}
+
+impl NetMessage {
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(self).unwrap_or_default()
+    }
+
+    pub fn decode(data: &[u8]) -> Option<NetMessage> {
+        serde_json::from_slice(data).ok()
+    }
+}
@@ -0,0 +1,5 @@
+﻿pub mod c_input_frame;
+pub mod e_net_message;
+pub mod c_lockstep;
+pub mod c_lobby;
+pub mod c_net_game;
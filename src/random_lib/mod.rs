@@ -0,0 +1 @@
+﻿pub mod c_sim_rng;
\ No newline at end of file
@@ -0,0 +1,207 @@
+﻿use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use vek::Vec2;
+use crate::assetsdb_lib::e_asset::Asset;
+use crate::assetsdb_lib::t_file_readable::FileReadable;
+use crate::assetsdb_lib::t_from_assetref::FromAssetRef;
+use crate::render_lib::f_drawers::draw_line_thick;
+use crate::render_lib::t_screen_data::Screen;
+
+/// One plane of stars. `parallax` is how much of the camera motion the plane follows,
+/// far planes use small values.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StarLayer {
+    pub count: u32,
+    pub parallax: f32,
+    /// Constant motion in px/s, independent of the camera.
+    #[serde(default)]
+    pub drift: [f32; 2],
+    pub size: i32,
+    /// Motion streak length in seconds of camera velocity, `0` draws plain points.
+    #[serde(default)]
+    pub streak: f32,
+    pub color: [u8; 3],
+}
+
+/// Loose line-art clouds drawn behind the stars.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct NebulaSettings {
+    pub count: u32,
+    pub rings: u32,
+    pub radius_min: f32,
+    pub radius_max: f32,
+    pub parallax: f32,
+    pub color: [u8; 3],
+}
+
+/// Per level backdrop description, loaded from `.bg` files.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct BackgroundSettings {
+    pub name: String,
+    pub seed: u64,
+    #[serde(default)]
+    pub star_layers: Vec<StarLayer>,
+    #[serde(default)]
+    pub nebula: NebulaSettings,
+}
+
+impl FileReadable for BackgroundSettings {}
+
+impl FromAssetRef for BackgroundSettings {
+    fn from_asset(a: &Asset) -> Option<&Self> {
+        match a {
+            Asset::Background(b) => Some(b),
+            _ => None,
+        }
+    }
+}
+
+struct StarPlane {
+    stars: Vec<Vec2<f32>>,
+    offset: Vec2<f32>,
+    layer: StarLayer,
+}
+
+struct NebulaCloud {
+    center: Vec2<f32>,
+    /// Closed outlines relative to `center`.
+    rings: Vec<Vec<Vec2<f32>>>,
+    radius: f32,
+}
+
+/// Parallax backdrop drawn into `Screen` before any entity.
+/// Everything is generated from the settings seed, so a level always gets the same sky.
+#[derive(Default)]
+pub struct Background {
+    size: Vec2<f32>,
+    planes: Vec<StarPlane>,
+    clouds: Vec<NebulaCloud>,
+    nebula_offset: Vec2<f32>,
+    nebula_parallax: f32,
+    nebula_color: u32,
+    camera_velocity: Vec2<f32>,
+}
+
+impl Background {
+    /// `variant` is mixed into the seed, e.g. the level number when several levels share settings.
+    pub fn new(settings: &BackgroundSettings, size: Vec2<usize>, variant: u64) -> Background {
+        let mut rng = StdRng::seed_from_u64(settings.seed ^ variant.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        let size = Vec2::new(size.x as f32, size.y as f32);
+
+        let planes = settings.star_layers.iter()
+            .map(|layer| StarPlane {
+                stars: (0..layer.count)
+                    .map(|_| Vec2::new(rng.random_range(0.0..size.x), rng.random_range(0.0..size.y)))
+                    .collect(),
+                offset: Vec2::zero(),
+                layer: layer.clone(),
+            })
+            .collect();
+
+        let nebula = &settings.nebula;
+        let clouds = (0..nebula.count)
+            .map(|_| make_cloud(&mut rng, nebula, size))
+            .collect();
+
+        Background {
+            size,
+            planes,
+            clouds,
+            nebula_offset: Vec2::zero(),
+            nebula_parallax: nebula.parallax,
+            nebula_color: pack_color(nebula.color),
+            camera_velocity: Vec2::zero(),
+        }
+    }
+
+    /// Scrolls the planes against `camera_velocity`, usually the player velocity.
+    pub fn update(&mut self, delta_time: f32, camera_velocity: Vec2<f32>) {
+        self.camera_velocity = camera_velocity;
+
+        for plane in self.planes.iter_mut() {
+            let drift = Vec2::from(plane.layer.drift);
+            plane.offset = wrap(plane.offset + (drift - camera_velocity * plane.layer.parallax) * delta_time, self.size);
+        }
+        self.nebula_offset = wrap(self.nebula_offset - camera_velocity * self.nebula_parallax * delta_time, self.size);
+    }
+
+    pub fn draw(&self, screen: &mut Screen) {
+        self.draw_nebula(screen);
+
+        for plane in self.planes.iter() {
+            let color = pack_color(plane.layer.color);
+            let tail = self.camera_velocity * plane.layer.parallax * plane.layer.streak;
+
+            for star in plane.stars.iter() {
+                let p = wrap(*star + plane.offset, self.size);
+                draw_line_thick(screen, p.x + tail.x, p.y + tail.y, p.x, p.y, plane.layer.size, color);
+            }
+        }
+    }
+
+    fn draw_nebula(&self, screen: &mut Screen) {
+        for cloud in self.clouds.iter() {
+            let center = wrap(cloud.center + self.nebula_offset, self.size);
+
+            // a cloud near an edge is drawn again on the opposite side
+            for ox in [-self.size.x, 0.0, self.size.x] {
+                for oy in [-self.size.y, 0.0, self.size.y] {
+                    let c = center + Vec2::new(ox, oy);
+                    if c.x + cloud.radius < 0.0 || c.y + cloud.radius < 0.0
+                        || c.x - cloud.radius > self.size.x || c.y - cloud.radius > self.size.y {
+                        continue;
+                    }
+
+                    for ring in cloud.rings.iter() {
+                        for (i, a) in ring.iter().enumerate() {
+                            let b = ring[(i + 1) % ring.len()];
+                            draw_line_thick(screen, c.x + a.x, c.y + a.y, c.x + b.x, c.y + b.y, 1, self.nebula_color);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn make_cloud(rng: &mut StdRng, settings: &NebulaSettings, size: Vec2<f32>) -> NebulaCloud {
+    let radius = if settings.radius_max > settings.radius_min {
+        rng.random_range(settings.radius_min..settings.radius_max)
+    } else {
+        settings.radius_min
+    };
+    let points = 24;
+    let phase: f32 = rng.random_range(0.0..std::f32::consts::TAU);
+    let lobes = rng.random_range(2..5) as f32;
+
+    // concentric wobbly outlines, shrinking towards the core
+    let rings = (0..settings.rings.max(1))
+        .map(|r| {
+            let ring_radius = radius * (1.0 - r as f32 / (settings.rings.max(1) as f32 + 1.0));
+            (0..points)
+                .map(|i| {
+                    let angle = i as f32 / points as f32 * std::f32::consts::TAU;
+                    let wobble = 1.0 + 0.25 * (angle * lobes + phase + r as f32).sin() + rng.random_range(-0.08..0.08);
+                    Vec2::new(angle.cos(), angle.sin()) * ring_radius * wobble
+                })
+                .collect()
+        })
+        .collect();
+
+    NebulaCloud {
+        center: Vec2::new(rng.random_range(0.0..size.x), rng.random_range(0.0..size.y)),
+        rings,
+        radius: radius * 1.35,
+    }
+}
+
+#[inline]
+fn wrap(p: Vec2<f32>, size: Vec2<f32>) -> Vec2<f32> {
+    Vec2::new(p.x.rem_euclid(size.x), p.y.rem_euclid(size.y))
+}
+
+#[inline]
+fn pack_color(c: [u8; 3]) -> u32 {
+    ((c[0] as u32) << 16) | ((c[1] as u32) << 8) | c[2] as u32
+}
@@ -0,0 +1,178 @@
+﻿use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+use std::time::{SystemTime, UNIX_EPOCH};
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, ImageError, ImageResult, Rgba, RgbaImage};
+use crate::render_lib::t_screen_data::Screen;
+
+/// Copies the `Screen` buffer (0x00RRGGBB) into an opaque RGBA image.
+/// egui is drawn on the GPU afterwards, so it is not part of the capture.
+pub fn screen_to_image(screen: &Screen) -> RgbaImage {
+    let buffer = screen.get_buffer();
+    RgbaImage::from_fn(screen.width() as u32, screen.height() as u32, |x, y| {
+        let p = buffer[x as usize + y as usize * screen.width()];
+        Rgba([((p >> 16) & 0xFF) as u8, ((p >> 8) & 0xFF) as u8, (p & 0xFF) as u8, 0xFF])
+    })
+}
+
+pub fn save_screenshot(screen: &Screen, path: impl AsRef<Path>) -> ImageResult<()> {
+    if let Some(parent) = path.as_ref().parent() {
+        fs::create_dir_all(parent)?;
+    }
+    screen_to_image(screen).save(path)
+}
+
+/// `captures/<prefix>_<unix ms>.<extension>` next to the working directory.
+pub fn capture_path(prefix: &str, extension: &str) -> PathBuf {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+
+    let name = if extension.is_empty() {
+        format!("{prefix}_{stamp}")
+    } else {
+        format!("{prefix}_{stamp}.{extension}")
+    };
+    PathBuf::from("captures").join(name)
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum RecordFormat {
+    Gif,
+    PngSequence,
+}
+
+impl RecordFormat {
+    /// `.gif` records an animation, anything else is a folder of numbered PNGs.
+    pub fn from_path(path: &Path) -> RecordFormat {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("gif") => RecordFormat::Gif,
+            _ => RecordFormat::PngSequence,
+        }
+    }
+}
+
+/// Encodes frames on a worker thread so recording does not stall the game loop.
+pub struct FrameRecorder {
+    path: PathBuf,
+    sender: Option<mpsc::Sender<(RgbaImage, u32)>>,
+    worker: Option<JoinHandle<ImageResult<usize>>>,
+}
+
+impl FrameRecorder {
+    pub fn new(path: PathBuf) -> FrameRecorder {
+        let format = RecordFormat::from_path(&path);
+        let (sender, receiver) = mpsc::channel::<(RgbaImage, u32)>();
+
+        let out = path.clone();
+        let worker = std::thread::spawn(move || -> ImageResult<usize> {
+            match format {
+                RecordFormat::Gif => {
+                    if let Some(parent) = out.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+                    let mut encoder = GifEncoder::new_with_speed(BufWriter::new(File::create(&out)?), 10);
+                    encoder.set_repeat(Repeat::Infinite)?;
+
+                    let mut count = 0;
+                    for (image, delay_ms) in receiver {
+                        encoder.encode_frame(Frame::from_parts(image, 0, 0, Delay::from_numer_denom_ms(delay_ms, 1)))?;
+                        count += 1;
+                    }
+                    Ok(count)
+                }
+                RecordFormat::PngSequence => {
+                    fs::create_dir_all(&out)?;
+
+                    let mut count = 0;
+                    for (image, _) in receiver {
+                        image.save(out.join(format!("frame_{count:05}.png")))?;
+                        count += 1;
+                    }
+                    Ok(count)
+                }
+            }
+        });
+
+        FrameRecorder {
+            path,
+            sender: Some(sender),
+            worker: Some(worker),
+        }
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn push_frame(&self, screen: &Screen, delay_ms: u32) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send((screen_to_image(screen), delay_ms.max(1)));
+        }
+    }
+
+    /// Waits for the encoder to flush and returns the number of written frames.
+    pub fn finish(mut self) -> ImageResult<usize> {
+        self.sender = None;
+        match self.worker.take() {
+            Some(worker) => worker.join()
+                .unwrap_or_else(|_| Err(ImageError::IoError(io::Error::other("frame encoder thread panicked")))),
+            None => Ok(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_screen() -> Screen {
+        let mut screen = Screen::new(8, 4);
+        for (i, p) in screen.get_buffer_mut().iter_mut().enumerate() {
+            *p = (i as u32 * 0x0A0B0C) & 0x00FF_FFFF;
+        }
+        screen
+    }
+
+    fn assert_matches(image: &RgbaImage, screen: &Screen) {
+        assert_eq!(image.dimensions(), (screen.width() as u32, screen.height() as u32));
+        assert_eq!(image.as_raw(), screen_to_image(screen).as_raw());
+    }
+
+    #[test]
+    fn screenshot_round_trips_through_png() {
+        let dir = std::env::temp_dir().join(format!("asteroids_capture_{}", std::process::id()));
+        let path = dir.join("shot.png");
+        let screen = test_screen();
+
+        save_screenshot(&screen, &path).unwrap();
+        let image = image::open(&path).unwrap().to_rgba8();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_matches(&image, &screen);
+        assert_eq!(image.get_pixel(1, 0), &Rgba([0x0A, 0x0B, 0x0C, 0xFF]));
+    }
+
+    #[test]
+    fn recorder_writes_numbered_frames() {
+        let dir = std::env::temp_dir().join(format!("asteroids_record_{}", std::process::id()));
+        let screen = test_screen();
+
+        let recorder = FrameRecorder::new(dir.clone());
+        recorder.push_frame(&screen, 16);
+        recorder.push_frame(&screen, 16);
+        let count = recorder.finish().unwrap();
+
+        let second = image::open(dir.join("frame_00001.png")).unwrap().to_rgba8();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(count, 2);
+        assert_matches(&second, &screen);
+    }
+}
@@ -0,0 +1,148 @@
+﻿use serde::{Deserialize, Serialize};
+use crate::assetsdb_lib::e_asset::Asset;
+use crate::assetsdb_lib::t_file_readable::FileReadable;
+use crate::assetsdb_lib::t_file_writable::FileWritable;
+use crate::assetsdb_lib::t_from_assetref::FromAssetRef;
+use std::rc::Rc;
+
+#[derive(Serialize, Deserialize, Copy, Clone)]
+pub enum EmitterMode {
+    /// Spawns `count` particles at once.
+    Burst { count: u32 },
+    /// Spawns `rate` particles per second while active.
+    Continuous { rate: f32 },
+}
+
+impl Default for EmitterMode {
+    fn default() -> Self {
+        EmitterMode::Burst { count: 0 }
+    }
+}
+
+/// Particle effect description loaded from a `.pfx` file.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct ParticleEffect {
+    name: String,
+    mode: EmitterMode,
+
+    pub speed_min: f32,
+    pub speed_max: f32,
+    /// Full cone angle in radians around the emit direction, `TAU` emits in every direction.
+    pub spread: f32,
+    pub life_min: f32,
+    pub life_max: f32,
+    /// Fraction of velocity lost per second.
+    pub drag: f32,
+    /// Part of the emitter velocity added to every particle.
+    #[serde(default)]
+    pub inherit_velocity: f32,
+    /// Streak length in seconds of travel, `0` draws a point.
+    #[serde(default)]
+    pub length: f32,
+    pub size: i32,
+
+    pub color_start: [u8; 3],
+    pub color_end: [u8; 3],
+}
+
+impl FileWritable for ParticleEffect {}
+
+impl FileReadable for ParticleEffect {}
+
+impl FromAssetRef for ParticleEffect {
+    fn from_asset(a: &Asset) -> Option<&Self> {
+        match a {
+            Asset::Particles(p) => Some(p),
+            _ => None,
+        }
+    }
+}
+
+impl ParticleEffect {
+    pub fn get_mode(&self) -> EmitterMode { self.mode }
+
+    pub fn burst_count(&self) -> u32 {
+        match self.mode {
+            EmitterMode::Burst { count } => count,
+            EmitterMode::Continuous { .. } => 0,
+        }
+    }
+}
+
+/// Keeps the spawn accumulator of a continuous effect and the fired flag of a burst between frames.
+#[derive(Clone, Default)]
+pub struct ParticleEmitter {
+    effect: Rc<ParticleEffect>,
+    accumulator: f32,
+    fired: bool,
+}
+
+impl ParticleEmitter {
+    pub fn new(effect: Rc<ParticleEffect>) -> ParticleEmitter {
+        ParticleEmitter { effect, accumulator: 0.0, fired: false }
+    }
+
+    pub fn get_effect(&self) -> &Rc<ParticleEffect> {
+        &self.effect
+    }
+
+    /// How many particles to spawn this frame. A burst fires once per activation,
+    /// an inactive emitter drops its accumulated fraction and rearms its burst.
+    pub fn update(&mut self, delta_time: f32, active: bool) -> u32 {
+        if !active {
+            self.accumulator = 0.0;
+            self.fired = false;
+            return 0;
+        }
+
+        match self.effect.get_mode() {
+            EmitterMode::Burst { count } => {
+                if self.fired {
+                    return 0;
+                }
+                self.fired = true;
+                count
+            }
+            EmitterMode::Continuous { rate } => {
+                self.accumulator += rate * delta_time;
+                let count = self.accumulator.floor();
+                self.accumulator -= count;
+                count as u32
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn emitter(mode: EmitterMode) -> ParticleEmitter {
+        ParticleEmitter::new(Rc::new(ParticleEffect { mode, ..Default::default() }))
+    }
+
+    #[test]
+    fn burst_fires_once_per_activation() {
+        let mut e = emitter(EmitterMode::Burst { count: 12 });
+
+        assert_eq!(e.update(0.016, true), 12);
+        assert_eq!(e.update(0.016, true), 0);
+        assert_eq!(e.update(0.016, true), 0);
+
+        assert_eq!(e.update(0.016, false), 0);
+        assert_eq!(e.update(0.016, true), 12);
+    }
+
+    #[test]
+    fn continuous_rate_accumulates_fractions() {
+        let mut e = emitter(EmitterMode::Continuous { rate: 6.0 });
+
+        // 6/s at 0.25s is 1.5 particles a frame, the half carries over.
+        let spawned: Vec<u32> = (0..4).map(|_| e.update(0.25, true)).collect();
+        assert_eq!(spawned, vec![1, 2, 1, 2]);
+
+        e.update(0.25, true);
+        e.update(0.25, false);
+        assert_eq!(e.update(0.25, true), 1);
+    }
+}
@@ -0,0 +1,160 @@
+﻿use rand::Rng;
+use vek::Vec2;
+use crate::render_lib::c_particle_effect::ParticleEffect;
+use crate::render_lib::f_drawers::draw_line_thick;
+use crate::render_lib::t_screen_data::Screen;
+
+#[derive(Copy, Clone)]
+struct Particle {
+    position: Vec2<f32>,
+    velocity: Vec2<f32>,
+    age: f32,
+    life: f32,
+
+    drag: f32,
+    length: f32,
+    size: i32,
+    color_start: [u8; 3],
+    color_end: [u8; 3],
+}
+
+/// Fixed capacity particle pool. Dead particles are swap-removed, spawns beyond capacity are dropped.
+pub struct ParticleSystem {
+    particles: Vec<Particle>,
+    capacity: usize,
+}
+
+impl Default for ParticleSystem {
+    fn default() -> Self {
+        ParticleSystem::new(4096)
+    }
+}
+
+impl ParticleSystem {
+    pub fn new(capacity: usize) -> ParticleSystem {
+        ParticleSystem {
+            particles: Vec::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn burst(&mut self, effect: &ParticleEffect, position: Vec2<f32>, dir: Vec2<f32>, velocity: Vec2<f32>) {
+        self.emit(effect, effect.burst_count(), position, dir, velocity);
+    }
+
+    pub fn emit(&mut self, effect: &ParticleEffect, count: u32, position: Vec2<f32>, dir: Vec2<f32>, velocity: Vec2<f32>) {
+        let mut rng = rand::rng();
+        let base_angle = dir.y.atan2(dir.x);
+        let half_spread = effect.spread.abs() / 2.0;
+
+        for _ in 0..count {
+            if self.particles.len() >= self.capacity {
+                return;
+            }
+
+            let angle = base_angle + random_range(&mut rng, -half_spread, half_spread);
+            let speed = random_range(&mut rng, effect.speed_min, effect.speed_max);
+            let (s, c) = angle.sin_cos();
+
+            self.particles.push(Particle {
+                position,
+                velocity: Vec2::new(c, s) * speed + velocity * effect.inherit_velocity,
+                age: 0.0,
+                life: random_range(&mut rng, effect.life_min, effect.life_max).max(0.01),
+                drag: effect.drag,
+                length: effect.length,
+                size: effect.size.max(1),
+                color_start: effect.color_start,
+                color_end: effect.color_end,
+            });
+        }
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        let mut i = 0;
+        while i < self.particles.len() {
+            let p = &mut self.particles[i];
+            p.age += delta_time;
+
+            if p.age >= p.life {
+                self.particles.swap_remove(i);
+                continue;
+            }
+
+            p.velocity *= (1.0 - p.drag * delta_time).max(0.0);
+            p.position += p.velocity * delta_time;
+            i += 1;
+        }
+    }
+
+    pub fn draw(&self, screen: &mut Screen) {
+        let w = screen.width() as f32;
+        let h = screen.height() as f32;
+
+        for p in self.particles.iter() {
+            let color = lerp_color(p.color_start, p.color_end, p.age / p.life);
+            let head = Vec2::new(p.position.x.rem_euclid(w), p.position.y.rem_euclid(h));
+            let tail = head - p.velocity * p.length;
+
+            draw_line_thick(screen, tail.x, tail.y, head.x, head.y, p.size, color);
+        }
+    }
+}
+
+#[inline]
+fn random_range(rng: &mut impl Rng, min: f32, max: f32) -> f32 {
+    if max > min { rng.random_range(min..max) } else { min }
+}
+
+fn lerp_color(from: [u8; 3], to: [u8; 3], t: f32) -> u32 {
+    let t = t.clamp(0.0, 1.0);
+    let channel = |i: usize| (from[i] as f32 + (to[i] as f32 - from[i] as f32) * t) as u32;
+    (channel(0) << 16) | (channel(1) << 8) | channel(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn effect(life: f32, drag: f32) -> ParticleEffect {
+        let mut effect = ParticleEffect::default();
+        effect.speed_min = 100.0;
+        effect.speed_max = 100.0;
+        effect.life_min = life;
+        effect.life_max = life;
+        effect.drag = drag;
+        effect
+    }
+
+    #[test]
+    fn particles_die_after_their_lifetime() {
+        let mut system = ParticleSystem::new(16);
+        system.emit(&effect(0.5, 0.0), 4, Vec2::zero(), Vec2::unit_x(), Vec2::zero());
+        assert_eq!(system.particles.len(), 4);
+
+        system.update(0.25);
+        assert_eq!(system.particles.len(), 4);
+
+        system.update(0.3);
+        assert!(system.particles.is_empty());
+    }
+
+    #[test]
+    fn drag_slows_particles_down() {
+        let mut system = ParticleSystem::new(16);
+        system.emit(&effect(10.0, 0.5), 1, Vec2::zero(), Vec2::unit_x(), Vec2::zero());
+
+        system.update(0.1);
+        let p = system.particles[0];
+        assert!((p.velocity.x - 95.0).abs() < 1e-3);
+        assert!((p.position.x - 9.5).abs() < 1e-3);
+        assert!(p.velocity.y.abs() < 1e-3);
+    }
+
+    #[test]
+    fn spawns_past_capacity_are_dropped() {
+        let mut system = ParticleSystem::new(3);
+        system.emit(&effect(1.0, 0.0), 10, Vec2::zero(), Vec2::unit_x(), Vec2::zero());
+        assert_eq!(system.particles.len(), 3);
+    }
+}
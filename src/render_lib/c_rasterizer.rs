@@ -0,0 +1,337 @@
+﻿use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+/// Side of a square screen tile in pixels.
+pub const TILE_SIZE: usize = 64;
+/// Below this many commands a frame is rasterized on the calling thread.
+const PARALLEL_MIN_COMMANDS: usize = 64;
+
+#[derive(Copy, Clone, Debug)]
+pub enum DrawCommand {
+    Line { x0: i32, y0: i32, x1: i32, y1: i32, thickness: i32, color: u32 },
+}
+
+impl DrawCommand {
+    /// Inclusive pixel bounds touched by the command.
+    fn bounds(&self) -> (i32, i32, i32, i32) {
+        match *self {
+            DrawCommand::Line { x0, y0, x1, y1, thickness, .. } => {
+                let pad = thickness.max(1) / 2;
+                (x0.min(x1) - pad, y0.min(y1) - pad, x0.max(x1) + pad, y0.max(y1) + pad)
+            }
+        }
+    }
+
+    fn raster(&self, target: &mut TileTarget) {
+        match *self {
+            DrawCommand::Line { x0, y0, x1, y1, thickness, color } => {
+                raster_line(target, x0, y0, x1, y1, thickness, color);
+            }
+        }
+    }
+}
+
+/// One tile of a horizontal band of the framebuffer. Writes outside the tile are dropped,
+/// so every tile can be rasterized independently of the others.
+struct TileTarget<'a> {
+    band: &'a mut [u32],
+    width: usize,
+    band_y: i32,
+    min_x: i32,
+    max_x: i32,
+    min_y: i32,
+    max_y: i32,
+}
+
+impl TileTarget<'_> {
+    #[inline]
+    fn put(&mut self, x: i32, y: i32, color: u32) {
+        if x < self.min_x || x >= self.max_x || y < self.min_y || y >= self.max_y {
+            return;
+        }
+        let pos = x as usize + (y - self.band_y) as usize * self.width;
+        self.band[pos] = color;
+    }
+}
+
+/// Bins draw commands into screen tiles and rasterizes tile rows on a persistent worker pool.
+/// Commands keep their submission order inside every tile, so output matches a serial draw.
+#[derive(Default)]
+pub struct TileRasterizer {
+    bins: Vec<Vec<u32>>,
+    /// Worker count, `0` picks the available parallelism.
+    threads: usize,
+    /// Started on the first parallel frame and kept until the worker count changes.
+    pool: Option<ThreadPool>,
+}
+
+impl TileRasterizer {
+    pub fn set_threads(&mut self, threads: usize) {
+        if self.threads != threads {
+            self.threads = threads;
+            self.pool = None;
+        }
+    }
+
+    pub fn rasterize(&mut self, buffer: &mut [u32], width: usize, height: usize, commands: &[DrawCommand]) {
+        if commands.is_empty() || width == 0 || height == 0 {
+            return;
+        }
+
+        let tiles_x = width.div_ceil(TILE_SIZE);
+        let tiles_y = height.div_ceil(TILE_SIZE);
+        self.bin(commands, width, height, tiles_x, tiles_y);
+
+        let bins = &self.bins;
+
+        if self.threads == 1 || tiles_y == 1 || commands.len() < PARALLEL_MIN_COMMANDS {
+            for (ty, band) in buffer.chunks_mut(TILE_SIZE * width).enumerate() {
+                raster_band(ty, band, width, tiles_x, bins, commands);
+            }
+            return;
+        }
+
+        let threads = self.threads;
+        let pool = self.pool.get_or_insert_with(|| {
+            ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .thread_name(|i| format!("raster-{i}"))
+                .build()
+                .expect("failed to start raster threads")
+        });
+
+        pool.install(|| {
+            buffer.par_chunks_mut(TILE_SIZE * width).enumerate().for_each(|(ty, band)| {
+                raster_band(ty, band, width, tiles_x, bins, commands);
+            });
+        });
+    }
+
+    fn bin(&mut self, commands: &[DrawCommand], width: usize, height: usize, tiles_x: usize, tiles_y: usize) {
+        self.bins.resize_with(tiles_x * tiles_y, Vec::new);
+        self.bins.truncate(tiles_x * tiles_y);
+        for bin in self.bins.iter_mut() {
+            bin.clear();
+        }
+
+        for (i, command) in commands.iter().enumerate() {
+            let (min_x, min_y, max_x, max_y) = command.bounds();
+            if max_x < 0 || max_y < 0 || min_x >= width as i32 || min_y >= height as i32 {
+                continue;
+            }
+
+            let tx0 = min_x.max(0) as usize / TILE_SIZE;
+            let ty0 = min_y.max(0) as usize / TILE_SIZE;
+            let tx1 = (max_x as usize).min(width - 1) / TILE_SIZE;
+            let ty1 = (max_y as usize).min(height - 1) / TILE_SIZE;
+
+            for ty in ty0..=ty1 {
+                for tx in tx0..=tx1 {
+                    self.bins[ty * tiles_x + tx].push(i as u32);
+                }
+            }
+        }
+    }
+}
+
+fn raster_band(ty: usize, band: &mut [u32], width: usize, tiles_x: usize, bins: &[Vec<u32>], commands: &[DrawCommand]) {
+    let band_y = (ty * TILE_SIZE) as i32;
+    let band_h = (band.len() / width) as i32;
+
+    for tx in 0..tiles_x {
+        let bin = &bins[ty * tiles_x + tx];
+        if bin.is_empty() {
+            continue;
+        }
+
+        let mut target = TileTarget {
+            band: &mut *band,
+            width,
+            band_y,
+            min_x: (tx * TILE_SIZE) as i32,
+            max_x: ((tx + 1) * TILE_SIZE).min(width) as i32,
+            min_y: band_y,
+            max_y: band_y + band_h,
+        };
+
+        for &i in bin.iter() {
+            commands[i as usize].raster(&mut target);
+        }
+    }
+}
+
+/// Bresenham line widened by `thickness` pixels across its minor axis.
+fn raster_line(target: &mut TileTarget, x0: i32, y0: i32, x1: i32, y1: i32, thickness: i32, color: u32) {
+    let t = thickness.max(1);
+    let neg = (t - 1) / 2;
+    let pos = t / 2;
+
+    if (y1 - y0).abs() < (x1 - x0).abs() {
+        if x0 > x1 {
+            plot_line_low(target, x1, y1, x0, y0, neg, pos, color);
+        } else {
+            plot_line_low(target, x0, y0, x1, y1, neg, pos, color);
+        }
+    } else if y0 > y1 {
+        plot_line_high(target, x1, y1, x0, y0, neg, pos, color);
+    } else {
+        plot_line_high(target, x0, y0, x1, y1, neg, pos, color);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn plot_line_low(target: &mut TileTarget, x0: i32, y0: i32, x1: i32, y1: i32, neg: i32, pos: i32, color: u32) {
+    let dx = x1 - x0;
+    let mut dy = y1 - y0;
+    let mut yi = 1;
+    if dy < 0 {
+        yi = -1;
+        dy = -dy;
+    }
+
+    let Some((first, last)) = clip_steps(
+        (x0, dx, target.min_x, target.max_x),
+        (y0, (yi * dy) as f32 / dx as f32, target.min_y, target.max_y),
+        neg.max(pos),
+    ) else {
+        return;
+    };
+
+    let (offset, mut d) = bresenham_state(dx, dy, first);
+    let mut y = y0 + yi * offset;
+
+    for x in x0 + first..=x0 + last {
+        for oy in -neg..=pos {
+            target.put(x, y + oy, color);
+        }
+
+        if d > 0 {
+            y += yi;
+            d += 2 * (dy - dx);
+        } else {
+            d += 2 * dy;
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn plot_line_high(target: &mut TileTarget, x0: i32, y0: i32, x1: i32, y1: i32, neg: i32, pos: i32, color: u32) {
+    let mut dx = x1 - x0;
+    let dy = y1 - y0;
+    let mut xi = 1;
+    if dx < 0 {
+        xi = -1;
+        dx = -dx;
+    }
+
+    let slope = if dy == 0 { 0.0 } else { (xi * dx) as f32 / dy as f32 };
+    let Some((first, last)) = clip_steps(
+        (y0, dy, target.min_y, target.max_y),
+        (x0, slope, target.min_x, target.max_x),
+        neg.max(pos),
+    ) else {
+        return;
+    };
+
+    let (offset, mut d) = bresenham_state(dy, dx, first);
+    let mut x = x0 + xi * offset;
+
+    for y in y0 + first..=y0 + last {
+        for ox in -neg..=pos {
+            target.put(x + ox, y, color);
+        }
+
+        if d > 0 {
+            x += xi;
+            d += 2 * (dx - dy);
+        } else {
+            d += 2 * dx;
+        }
+    }
+}
+
+/// Liang–Barsky on the step index of a line walked along its major axis: the steps that can
+/// touch the tile. `major` is `(start, steps, tile min, tile max)`, `minor` is
+/// `(start, slope per step, tile min, tile max)` and `reach` the widening across the minor axis.
+/// The minor range gets a pixel of slack, Bresenham strays up to half a pixel from the ideal line.
+fn clip_steps(major: (i32, i32, i32, i32), minor: (i32, f32, i32, i32), reach: i32) -> Option<(i32, i32)> {
+    let (start, steps, major_min, major_max) = major;
+    let (minor_start, slope, minor_min, minor_max) = minor;
+
+    let mut first = (major_min - start).max(0);
+    let mut last = (major_max - 1 - start).min(steps);
+
+    let low = (minor_min - reach - 1 - minor_start) as f32;
+    let high = (minor_max + reach - minor_start) as f32;
+    if slope == 0.0 {
+        if low > 0.0 || high < 0.0 {
+            return None;
+        }
+    } else {
+        let (a, b) = (low / slope, high / slope);
+        first = first.max(a.min(b).floor() as i32);
+        last = last.min(a.max(b).ceil() as i32);
+    }
+
+    (first <= last).then_some((first, last))
+}
+
+/// Minor-axis offset and decision value of a Bresenham walk (`major` >= `minor` >= 0) after
+/// `step` steps, so a clipped walk plots exactly the pixels of the full one.
+fn bresenham_state(major: i32, minor: i32, step: i32) -> (i32, i32) {
+    if step == 0 {
+        return (0, 2 * minor - major);
+    }
+    let (major, minor, step) = (major as i64, minor as i64, step as i64);
+    let offset = (2 * minor * step + major - 1).div_euclid(2 * major);
+    let d = 2 * minor * (step + 1) - major - 2 * major * offset;
+    (offset as i32, d as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Draws every line on a single tile covering the whole buffer, nothing is clipped away.
+    fn raster_untiled(width: usize, height: usize, commands: &[DrawCommand]) -> Vec<u32> {
+        let mut buffer = vec![0; width * height];
+        let mut target = TileTarget {
+            band: &mut buffer,
+            width,
+            band_y: 0,
+            min_x: 0,
+            max_x: width as i32,
+            min_y: 0,
+            max_y: height as i32,
+        };
+        for command in commands {
+            command.raster(&mut target);
+        }
+        buffer
+    }
+
+    #[test]
+    fn clipped_lines_match_the_full_walk() {
+        let (width, height) = (300, 200);
+        let mut seed = 12345u32;
+        let mut next = |range: i32| {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            (seed >> 8) as i32 % range
+        };
+
+        let commands: Vec<DrawCommand> = (0..400)
+            .map(|i| DrawCommand::Line {
+                x0: next(width as i32),
+                y0: next(height as i32),
+                x1: next(width as i32),
+                y1: next(height as i32),
+                thickness: 1 + next(3),
+                color: i + 1,
+            })
+            .collect();
+
+        let mut buffer = vec![0; width * height];
+        TileRasterizer::default().rasterize(&mut buffer, width, height, &commands);
+        assert!(buffer == raster_untiled(width, height, &commands));
+    }
+}
@@ -0,0 +1,197 @@
+﻿use crate::render_lib::t_screen_data::Screen;
+
+/// Effect played over the `Screen` when the scene changes.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Transition {
+    /// Instant switch.
+    #[default]
+    Cut,
+    /// The old frame fades to black, then the new scene fades in.
+    Fade,
+    /// A bright edge sweeps left to right, uncovering the new scene.
+    Wipe,
+    /// The old frame squashes into a line across the middle like a CRT switching off, the new
+    /// scene opens up from that line.
+    Collapse,
+}
+
+impl Transition {
+    pub fn duration(&self) -> f32 {
+        match self {
+            Transition::Cut => 0.0,
+            Transition::Fade => 0.4,
+            Transition::Wipe => 0.45,
+            Transition::Collapse => 0.6,
+        }
+    }
+}
+
+const EDGE_COLOR: u32 = 0x00FFFFFF;
+
+/// A running transition: the last frame of the old scene plus the elapsed time. The new scene
+/// is live from the first frame, the player only post-processes what it rendered.
+pub struct TransitionPlayer {
+    transition: Transition,
+    /// Old scene's last frame, as wide and high as the screen when it was taken.
+    snapshot: Vec<u32>,
+    /// Copy of the new frame for effects that read and write it, kept between frames.
+    scratch: Vec<u32>,
+    elapsed: f32,
+}
+
+impl TransitionPlayer {
+    pub fn new(transition: Transition, screen: &Screen) -> Self {
+        Self { transition, snapshot: screen.get_buffer().to_vec(), scratch: Vec::new(), elapsed: 0.0 }
+    }
+
+    /// `0..=1` over the duration.
+    fn progress(&self) -> f32 {
+        (self.elapsed / self.transition.duration().max(f32::EPSILON)).clamp(0.0, 1.0)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.progress() >= 1.0
+    }
+
+    pub fn advance(&mut self, dt: f32) {
+        self.elapsed += dt;
+    }
+
+    /// Mixes the old frame into the freshly rasterized new one.
+    pub fn apply(&mut self, screen: &mut Screen) {
+        let (width, height) = (screen.width(), screen.height());
+        let buffer = screen.get_buffer_mut();
+        if self.snapshot.len() != buffer.len() {
+            return;
+        }
+        let t = self.progress();
+
+        match self.transition {
+            Transition::Cut => {}
+            Transition::Fade => {
+                // old frame out in the first half, new frame in during the second
+                if t < 0.5 {
+                    buffer.copy_from_slice(&self.snapshot);
+                }
+                let brightness = (1.0 - 2.0 * t).abs();
+                for pixel in buffer.iter_mut() {
+                    *pixel = scale_color(*pixel, brightness);
+                }
+            }
+            Transition::Wipe => {
+                let edge = (t * width as f32) as usize;
+                for (row, old) in buffer.chunks_mut(width).zip(self.snapshot.chunks(width)) {
+                    if edge < width {
+                        row[edge..].copy_from_slice(&old[edge..]);
+                        row[edge] = EDGE_COLOR;
+                    }
+                }
+            }
+            Transition::Collapse => {
+                // squash the old frame during the first half, open the new one during the second
+                if t < 0.5 {
+                    squash_vertical(buffer, &self.snapshot, width, height, 1.0 - 2.0 * t);
+                } else {
+                    self.scratch.clear();
+                    self.scratch.extend_from_slice(buffer);
+                    squash_vertical(buffer, &self.scratch, width, height, 2.0 * t - 1.0);
+                }
+            }
+        }
+    }
+}
+
+/// Draws `source` scaled to `scale` of its height around the horizontal center line, black
+/// elsewhere, with a bright line in the middle while it is thin.
+fn squash_vertical(buffer: &mut [u32], source: &[u32], width: usize, height: usize, scale: f32) {
+    let center = height as f32 / 2.0;
+    let half = (center * scale).max(1.0);
+
+    for (y, row) in buffer.chunks_mut(width).enumerate() {
+        let offset = y as f32 + 0.5 - center;
+        if offset.abs() >= half {
+            row.fill(0);
+            continue;
+        }
+        let source_y = ((center + offset / half * center) as usize).min(height - 1);
+        row.copy_from_slice(&source[source_y * width..(source_y + 1) * width]);
+        if scale < 0.1 {
+            row.fill(scale_color(EDGE_COLOR, 1.0 - scale * 5.0));
+        }
+    }
+}
+
+fn scale_color(color: u32, factor: f32) -> u32 {
+    let channel = |shift: u32| ((((color >> shift) & 0xFF) as f32 * factor) as u32).min(0xFF) << shift;
+    channel(16) | channel(8) | channel(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen_filled(color: u32) -> Screen {
+        let mut screen = Screen::new(8, 4);
+        screen.get_buffer_mut().fill(color);
+        screen
+    }
+
+    #[test]
+    fn fade_goes_through_black() {
+        let old = screen_filled(0x00FF8040);
+        let mut player = TransitionPlayer::new(Transition::Fade, &old);
+        let mut screen = screen_filled(0x00FFFFFF);
+
+        player.apply(&mut screen);
+        assert_eq!(screen.get_buffer()[0], 0x00FF8040);
+
+        player.advance(Transition::Fade.duration() / 2.0);
+        let mut screen = screen_filled(0x00FFFFFF);
+        player.apply(&mut screen);
+        assert_eq!(screen.get_buffer()[0], 0);
+
+        player.advance(Transition::Fade.duration());
+        let mut screen = screen_filled(0x00FFFFFF);
+        player.apply(&mut screen);
+        assert_eq!(screen.get_buffer()[0], 0x00FFFFFF);
+        assert!(player.is_finished());
+    }
+
+    #[test]
+    fn wipe_uncovers_from_the_left() {
+        let old = screen_filled(0x00112233);
+        let mut player = TransitionPlayer::new(Transition::Wipe, &old);
+        player.advance(Transition::Wipe.duration() / 2.0);
+
+        let mut screen = screen_filled(0x00445566);
+        player.apply(&mut screen);
+        let row = &screen.get_buffer()[..8];
+        assert_eq!(row[0], 0x00445566);
+        assert_eq!(row[4], EDGE_COLOR);
+        assert_eq!(row[7], 0x00112233);
+    }
+
+    #[test]
+    fn collapse_squashes_into_the_center_line() {
+        let old = screen_filled(0x00112233);
+        let mut player = TransitionPlayer::new(Transition::Collapse, &old);
+
+        let mut screen = screen_filled(0x00445566);
+        player.apply(&mut screen);
+        assert!(screen.get_buffer().iter().all(|p| *p == 0x00112233));
+
+        // thinnest at the middle: only the rows around the center line stay lit
+        player.advance(Transition::Collapse.duration() / 2.0);
+        let mut screen = screen_filled(0x00445566);
+        player.apply(&mut screen);
+        let rows: Vec<u32> = screen.get_buffer().chunks(8).map(|row| row[0]).collect();
+        assert_eq!(rows, [0, EDGE_COLOR, EDGE_COLOR, 0]);
+
+        player.advance(Transition::Collapse.duration());
+        for _ in 0..2 {
+            let mut screen = screen_filled(0x00445566);
+            player.apply(&mut screen);
+            assert!(screen.get_buffer().iter().all(|p| *p == 0x00445566));
+        }
+    }
+}
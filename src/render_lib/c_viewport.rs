@@ -0,0 +1,121 @@
+﻿use rayon::prelude::*;
+use vek::Vec2;
+use crate::render_lib::t_screen_data::Screen;
+
+/// Surfaces smaller than this are converted on the calling thread.
+const BLIT_PARALLEL_MIN_PIXELS: usize = 512 * 512;
+
+/// Maps the fixed logical playfield (`Screen`) onto the physical window surface.
+/// The playfield keeps its aspect ratio and is centered, leftover space is letterboxed.
+#[derive(Debug, Copy, Clone)]
+pub struct Viewport {
+    logical: Vec2<usize>,
+    physical: Vec2<usize>,
+
+    scale: f32,
+    offset: Vec2<f32>,
+}
+
+impl Viewport {
+    pub fn new(logical: Vec2<usize>, physical: Vec2<usize>) -> Viewport {
+        let mut viewport = Viewport {
+            logical,
+            physical,
+            scale: 1.0,
+            offset: Vec2::zero(),
+        };
+        viewport.resize(physical);
+        viewport
+    }
+
+    pub fn resize(&mut self, physical: Vec2<usize>) {
+        self.physical = Vec2::new(physical.x.max(1), physical.y.max(1));
+
+        let sx = self.physical.x as f32 / self.logical.x.max(1) as f32;
+        let sy = self.physical.y as f32 / self.logical.y.max(1) as f32;
+        self.scale = sx.min(sy);
+
+        let scaled = Vec2::new(self.logical.x as f32, self.logical.y as f32) * self.scale;
+        self.offset = ((Vec2::new(self.physical.x as f32, self.physical.y as f32) - scaled) / 2.0).floor();
+    }
+
+    pub fn scale(&self) -> f32 { self.scale }
+
+    /// Physical window pixel -> playfield coordinate. `None` when the point lies in the letterbox.
+    pub fn physical_to_logical(&self, physical: Vec2<f32>) -> Option<Vec2<f32>> {
+        let p = (physical - self.offset) / self.scale;
+        if p.x < 0.0 || p.y < 0.0 || p.x >= self.logical.x as f32 || p.y >= self.logical.y as f32 {
+            return None;
+        }
+        Some(p)
+    }
+
+    /// Nearest-neighbour upscale of the `Screen` into an RGBA surface of `physical` size.
+    /// Large surfaces are split into row bands converted on the shared rayon pool.
+    pub fn blit(&self, dst_rgba: &mut [u8], screen: &Screen) {
+        let dst_w = self.physical.x;
+        let rows = dst_rgba.len() / (dst_w * 4);
+
+        let x_map: Vec<Option<usize>> = (0..dst_w)
+            .map(|x| self.source_index(x as f32 + 0.5 - self.offset.x, screen.width()))
+            .collect();
+
+        let workers = rayon::current_num_threads().min(rows.max(1));
+        if workers <= 1 || dst_w * rows < BLIT_PARALLEL_MIN_PIXELS {
+            self.blit_rows(dst_rgba, 0, screen, &x_map);
+            return;
+        }
+
+        let rows_per_worker = rows.div_ceil(workers);
+        dst_rgba.par_chunks_mut(rows_per_worker * dst_w * 4).enumerate().for_each(|(i, band)| {
+            self.blit_rows(band, i * rows_per_worker, screen, &x_map);
+        });
+    }
+
+    fn blit_rows(&self, dst_rgba: &mut [u8], first_row: usize, screen: &Screen, x_map: &[Option<usize>]) {
+        let src = screen.get_buffer();
+        let src_w = screen.width();
+        let dst_w = self.physical.x;
+        let identity = src_w == dst_w && self.scale == 1.0 && self.offset == Vec2::zero();
+
+        for (y, row) in dst_rgba.chunks_exact_mut(dst_w * 4).enumerate() {
+            let Some(sy) = self.source_index((first_row + y) as f32 + 0.5 - self.offset.y, screen.height()) else {
+                for px in row.chunks_exact_mut(4) {
+                    px.copy_from_slice(&[0, 0, 0, 0xFF]);
+                }
+                continue;
+            };
+
+            let src_row = &src[sy * src_w..(sy + 1) * src_w];
+            if identity {
+                for (dst, p) in row.chunks_exact_mut(4).zip(src_row.iter()) {
+                    dst.copy_from_slice(&to_rgba(*p));
+                }
+                continue;
+            }
+
+            for (dst, sx) in row.chunks_exact_mut(4).zip(x_map.iter()) {
+                let p = match sx {
+                    Some(sx) => src_row[*sx],
+                    None => 0,
+                };
+                dst.copy_from_slice(&to_rgba(p));
+            }
+        }
+    }
+
+    #[inline]
+    fn source_index(&self, physical: f32, len: usize) -> Option<usize> {
+        let v = (physical / self.scale).floor();
+        if v < 0.0 || v >= len as f32 {
+            return None;
+        }
+        Some(v as usize)
+    }
+}
+
+/// 0x00RRGGBB -> [R, G, B, 0xFF], branch free so the loops vectorize.
+#[inline]
+fn to_rgba(p: u32) -> [u8; 4] {
+    ((p << 8) | 0xFF).to_be_bytes()
+}
@@ -0,0 +1,13 @@
+﻿/// Coarse draw pass of a `Drawable`. Layers are drawn in declaration order,
+/// inside a layer lower `get_z_order` values are drawn first.
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum RenderLayer {
+    Background = 0,
+    World = 1,
+    Foreground = 2,
+}
+
+impl RenderLayer {
+    pub const ALL: [RenderLayer; 3] = [RenderLayer::Background, RenderLayer::World, RenderLayer::Foreground];
+}
@@ -1,121 +1,76 @@
-﻿use std::rc::Rc;
-use egui::load::SizedTexture;
-use egui::{Frame, Rect, Ui};
+﻿use egui::{Frame, Rect, Ui};
 use vek::Vec2;
+use crate::font_lib::c_stroke_font::StrokeFont;
+use crate::mesh_lib::c_mesh::Mesh;
+use crate::render_lib::c_rasterizer::DrawCommand;
 use crate::render_lib::t_screen_data::Screen;
-use crate::sprite_lib::c_sprite::SpriteTex;
+use crate::transform_lib::c_transform::Transform;
 
 pub fn draw_line_thick(screen: &mut Screen, x0f: f32, y0f: f32, x1f: f32, y1f: f32, thickness: i32, color: u32) {
-    let x0 = x0f.round() as i32;
-    let y0 = y0f.round() as i32;
-    let x1 = x1f.round() as i32;
-    let y1 = y1f.round() as i32;
-
-    let t = thickness.max(1);
-    let neg = (t - 1) / 2;
-    let pos = t / 2;
-
-    if (y1 - y0).abs() < (x1 - x0).abs() {
-        if x0 > x1 {
-            plot_line_low(screen, x1, y1, x0, y0, neg, pos, color);
-        } else {
-            plot_line_low(screen, x0, y0, x1, y1, neg, pos, color);
-        }
-    } else {
-        if y0 > y1 {
-            plot_line_high(screen, x1, y1, x0, y0, neg, pos, color);
-        } else {
-            plot_line_high(screen, x0, y0, x1, y1, neg, pos, color);
-        }
-    }
-
-    fn plot_line_low(screen: &mut Screen, x0: i32, y0: i32, x1: i32, y1: i32, neg: i32, pos: i32, color: u32) {
-        let dx = x1 - x0;
-        let mut dy = y1 - y0;
-        let mut yi = 1;
-        if dy < 0 {
-            yi = -1;
-            dy = -dy;
-        }
+    screen.push_command(DrawCommand::Line {
+        x0: x0f.round() as i32,
+        y0: y0f.round() as i32,
+        x1: x1f.round() as i32,
+        y1: y1f.round() as i32,
+        thickness,
+        color,
+    });
+}
 
-        let mut d = (2 * dy) - dx;
-        let mut y = y0;
+pub fn draw_mesh(screen: &mut Screen, transform: &Transform, mesh: &Mesh, color: u32) {
+    let radius = mesh.bounding_radius() * transform.get_max_scale();
 
-        for x in x0..=x1 {
-            for oy in -neg..=pos {
-                set_pixel(screen, x, y + oy, color);
-            }
+    for ghost in transform.get_wrap_ghosts(radius) {
+        for line in mesh.get_lines() {
+            let start = ghost.transform_point_to_world(line.start);
+            let end = ghost.transform_point_to_world(line.end);
 
-            if d > 0 {
-                y += yi;
-                d += 2 * (dy - dx);
-            } else {
-                d += 2 * dy;
-            }
+            draw_line_thick(screen, start.x, start.y, end.x, end.y, 3, color);
         }
     }
+}
 
-    fn plot_line_high(screen: &mut Screen, x0: i32, y0: i32, x1: i32, y1: i32, neg: i32, pos: i32, color: u32) {
-        let mut dx = x1 - x0;
-        let dy = y1 - y0;
-        let mut xi = 1;
-        if dx < 0 {
-            xi = -1;
-            dx = -dx;
+/// Draws `text` with a stroke font. `pos` is the top-left corner of the first glyph,
+/// `scale` is pixels per font unit and the whole block is rotated around `pos`.
+pub fn draw_text(screen: &mut Screen, font: &StrokeFont, text: &str, pos: Vec2<f32>, scale: f32, rotation: f32, color: u32) {
+    let thickness = ((scale * 0.35).round() as i32).max(1);
+    let (s, c) = rotation.sin_cos();
+    let to_screen = |p: Vec2<f32>| {
+        let p = p * scale;
+        Vec2::new(p.x * c - p.y * s, p.x * s + p.y * c) + pos
+    };
+
+    let mut cursor = Vec2::zero();
+    for ch in text.chars() {
+        if ch == '\n' {
+            cursor = Vec2::new(0.0, cursor.y + font.get_line_height());
+            continue;
         }
 
-        let mut d = (2 * dx) - dy;
-        let mut x = x0;
-
-        for y in y0..=y1 {
-            for ox in -neg..=pos {
-                set_pixel(screen, x + ox, y, color);
-            }
+        if let Some(glyph) = font.get_glyph(ch) {
+            for line in glyph.get_lines() {
+                let start = to_screen(line.start + cursor);
+                let end = to_screen(line.end + cursor);
 
-            if d > 0 {
-                x += xi;
-                d += 2 * (dx - dy);
-            } else {
-                d += 2 * dx;
+                draw_line_thick(screen, start.x, start.y, end.x, end.y, thickness, color);
             }
         }
+        cursor.x += font.get_advance();
     }
 }
 
-#[inline]
-fn wrap_i32(v: i32, m: i32) -> usize {
-    if (0..m).contains(&v) {
-        return v as usize;
-    }
-    if (-m..0).contains(&v) {
-        return (v + m) as usize;
-    }
-    if (m..2 * m).contains(&v) {
-        return (v - m) as usize;
-    }
-    v.rem_euclid(m) as usize
-}
-
-pub fn set_pixel(screen: &mut Screen, x: i32, y: i32, color: u32) {
-    let w = screen.width() as i32;
-    let h = screen.height() as i32;
-
-    let x = wrap_i32(x, w);
-    let y = wrap_i32(y, h);
-
-    let pos = x + screen.width() * y;
-    screen.get_buffer_mut()[pos] = color;
-}
-
-pub fn ui_draw_icon(ui: &mut egui::Ui, sprite: &Rc<SpriteTex>, size: Vec2<f32>) {
-    let tex = sprite.get_gui_texture();
-    let st = SizedTexture::new(tex.id(), tex.size_vec2());
-    ui.add(egui::Image::from_texture(st).fit_to_exact_size(egui::vec2(size.x, size.y)));
+pub fn ui_button_response(ui: &mut egui::Ui, name: &str) -> egui::Response {
+    let btn_size = egui::vec2(260.0, 30.0);
+    ui.add_sized(btn_size, egui::Button::new(name))
 }
 
-pub fn ui_button(ui: &mut egui::Ui, name: &str) -> bool{
-    let btn_size = egui::vec2(260.0, 30.0);
-    ui.add_sized(btn_size, egui::Button::new(name)).clicked()
+/// Outline drawn around the widget that has keyboard / gamepad focus.
+pub fn ui_focus_highlight(ui: &mut egui::Ui, rect: Rect) {
+    ui.painter().rect_stroke(
+        rect.expand(3.0),
+        egui::Rounding::same(6.0),
+        egui::Stroke::new(2.0, egui::Color32::from_rgb(255, 208, 64)),
+    );
 }
 
 pub fn ui_transparent_frame() -> egui::Frame{
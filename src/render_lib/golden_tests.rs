@@ -0,0 +1,200 @@
+﻿//! Golden image regression tests for the software renderer.
+//!
+//! Every test draws into an offscreen `Screen` and compares it with `tests/golden/<name>.png`.
+//! On mismatch the actual frame, the reference and a diff image go to `golden-diff/` in the target directory.
+//! Run with `UPDATE_GOLDEN=1 cargo test golden` to (re)write the references.
+
+use std::f32::consts::FRAC_PI_4;
+use std::path::PathBuf;
+use image::{Rgba, RgbaImage};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use vek::Vec2;
+use crate::assetsdb_lib::t_file_readable::FileReadable;
+use crate::font_lib::c_stroke_font::StrokeFont;
+use crate::mesh_lib::c_asteroid_generator::{generate_asteroid, AsteroidShape};
+use crate::mesh_lib::c_fracture::fracture_mesh;
+use crate::mesh_lib::c_mesh::Mesh;
+use crate::render_lib::c_background::{Background, BackgroundSettings};
+use crate::render_lib::c_frame_recorder::screen_to_image;
+use crate::render_lib::f_drawers::{draw_line_thick, draw_mesh, draw_text};
+use crate::render_lib::t_screen_data::Screen;
+use crate::transform_lib::c_transform::Transform;
+
+const SIZE: usize = 128;
+/// Max per channel difference before a pixel counts as changed.
+const CHANNEL_TOLERANCE: u8 = 8;
+/// Fraction of pixels allowed to change.
+const PIXEL_TOLERANCE: f32 = 0.001;
+
+fn golden_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden")
+}
+
+/// `golden-diff/` in the cargo target directory, `CARGO_TARGET_DIR` when it is set.
+fn diff_dir() -> PathBuf {
+    let target = std::env::var_os("CARGO_TARGET_DIR").map(PathBuf::from).unwrap_or_else(|| PathBuf::from("target"));
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(target).join("golden-diff")
+}
+
+fn new_screen() -> Screen {
+    let mut screen = Screen::new(SIZE, SIZE);
+    screen.flush();
+    screen
+}
+
+fn load_mesh(path: &str) -> Mesh {
+    let mut mesh = Mesh::default();
+    assert!(mesh.read_file(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(path)), "missing {path}");
+    mesh
+}
+
+fn assert_golden(name: &str, screen: &mut Screen) {
+    screen.rasterize();
+    let actual = screen_to_image(screen);
+    let path = golden_dir().join(format!("{name}.png"));
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::create_dir_all(golden_dir()).unwrap();
+        actual.save(&path).unwrap();
+        return;
+    }
+
+    let expected = match image::open(&path) {
+        Ok(img) => img.into_rgba8(),
+        Err(e) => panic!("no reference {} ({e}), run with UPDATE_GOLDEN=1", path.display()),
+    };
+    assert_eq!(expected.dimensions(), actual.dimensions(), "{name}: size changed");
+
+    let mut diff = RgbaImage::new(actual.width(), actual.height());
+    let mut changed = 0;
+    for (x, y, a) in actual.enumerate_pixels() {
+        let e = expected.get_pixel(x, y);
+        let delta = (0..3).map(|i| a[i].abs_diff(e[i])).max().unwrap_or(0);
+
+        if delta > CHANNEL_TOLERANCE {
+            changed += 1;
+            diff.put_pixel(x, y, Rgba([255, 0, 0, 255]));
+        } else {
+            let g = e[0] / 3;
+            diff.put_pixel(x, y, Rgba([g, g, g, 255]));
+        }
+    }
+
+    let ratio = changed as f32 / (actual.width() * actual.height()) as f32;
+    if ratio > PIXEL_TOLERANCE {
+        std::fs::create_dir_all(diff_dir()).unwrap();
+        actual.save(diff_dir().join(format!("{name}_actual.png"))).unwrap();
+        expected.save(diff_dir().join(format!("{name}_expected.png"))).unwrap();
+        diff.save(diff_dir().join(format!("{name}_diff.png"))).unwrap();
+        panic!("{name}: {changed} pixels differ ({:.3}%), see {}", ratio * 100.0, diff_dir().display());
+    }
+}
+
+#[test]
+fn golden_lines() {
+    let mut screen = new_screen();
+    let center = SIZE as f32 / 2.0;
+
+    for i in 0..16 {
+        let angle = i as f32 / 16.0 * std::f32::consts::TAU;
+        let end = Vec2::new(center, center) + Vec2::new(angle.cos(), angle.sin()) * 56.0;
+        draw_line_thick(&mut screen, center, center, end.x, end.y, 1 + i % 3, u32::MAX);
+    }
+
+    assert_golden("lines", &mut screen);
+}
+
+#[test]
+fn golden_lines_clipped() {
+    let mut screen = new_screen();
+
+    draw_line_thick(&mut screen, -40.0, 10.0, 170.0, 90.0, 3, 0xFF8040);
+    draw_line_thick(&mut screen, 64.0, -500.0, 20.0, 300.0, 5, 0x40FF80);
+    draw_line_thick(&mut screen, 200.0, 200.0, 300.0, 300.0, 3, u32::MAX);
+
+    assert_golden("lines_clipped", &mut screen);
+}
+
+#[test]
+fn golden_mesh_transform() {
+    let mut screen = new_screen();
+    let mesh = load_mesh("data/models/entity/player.mesh");
+
+    let transform = Transform::new(Vec2::new(64.0, 64.0), Vec2::new(0.4, 0.25), FRAC_PI_4, Vec2::new(SIZE, SIZE));
+    draw_mesh(&mut screen, &transform, &mesh, u32::MAX);
+
+    assert_golden("mesh_transform", &mut screen);
+}
+
+#[test]
+fn golden_mesh_wrap_ghosts() {
+    let mut screen = new_screen();
+    let mesh = load_mesh("data/models/meteors/asteroid_01.mesh");
+
+    let transform = Transform::new(Vec2::new(4.0, 124.0), Vec2::new(0.5, 0.5), 0.3, Vec2::new(SIZE, SIZE));
+    draw_mesh(&mut screen, &transform, &mesh, 0x80C0FF);
+
+    assert_golden("mesh_wrap_ghosts", &mut screen);
+}
+
+#[test]
+fn golden_text() {
+    let mut screen = new_screen();
+    let mut font = StrokeFont::default();
+    assert!(font.read_file(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("data/fonts/vector.vfont")));
+
+    draw_text(&mut screen, &font, "ABC 123\n+450!", Vec2::new(6.0, 8.0), 2.0, 0.0, u32::MAX);
+    draw_text(&mut screen, &font, "ROT", Vec2::new(30.0, 70.0), 3.0, -0.3, 0xFFD040);
+
+    assert_golden("text", &mut screen);
+}
+
+#[test]
+fn golden_background() {
+    let mut screen = new_screen();
+    let mut settings = BackgroundSettings::default();
+    assert!(settings.read_file(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("data/backgrounds/level_01.bg")));
+
+    let mut background = Background::new(&settings, Vec2::new(SIZE, SIZE), 1);
+    background.update(0.5, Vec2::new(200.0, -80.0));
+    background.draw(&mut screen);
+
+    assert_golden("background", &mut screen);
+}
+
+#[test]
+fn golden_generated_asteroids() {
+    let mut screen = new_screen();
+
+    for (i, scale) in [1.0, 0.6, 0.3, 0.15].into_iter().enumerate() {
+        let mesh = generate_asteroid(100 + i as u64, &AsteroidShape::for_scale(scale));
+        let pos = Vec2::new(32.0 + (i % 2) as f32 * 64.0, 32.0 + (i / 2) as f32 * 64.0);
+        let transform = Transform::new(pos, Vec2::new(0.4, 0.4), 0.0, Vec2::new(SIZE, SIZE));
+        draw_mesh(&mut screen, &transform, &mesh, u32::MAX);
+    }
+
+    assert_golden("generated_asteroids", &mut screen);
+}
+
+#[test]
+fn golden_fracture() {
+    let mut screen = new_screen();
+    let mut rng = StdRng::seed_from_u64(3);
+    let mesh = generate_asteroid(7, &AsteroidShape::default());
+    let parent = Transform::new(Vec2::new(64.0, 64.0), Vec2::new(0.7, 0.7), 0.0, Vec2::new(SIZE, SIZE));
+
+    let fragments = fracture_mesh(&mesh, Vec2::new(10.0, -5.0), 2, &mut rng);
+    let total: f32 = fragments.iter().map(|f| f.area_ratio).sum();
+    assert!(fragments.len() >= 3, "expected at least 3 pieces, got {}", fragments.len());
+    assert!((total - 1.0).abs() < 0.01, "fragments cover {total} of the parent area");
+
+    for fragment in fragments.iter() {
+        // pieces pushed slightly apart from the parent center
+        let pos = parent.transform_point_to_world(fragment.offset * 1.3);
+        let transform = Transform::new(pos, Vec2::new(0.7, 0.7), 0.0, Vec2::new(SIZE, SIZE));
+        draw_mesh(&mut screen, &transform, &fragment.mesh, u32::MAX);
+    }
+
+    assert_golden("fracture", &mut screen);
+}
@@ -1,3 +1,16 @@
 ﻿pub mod t_screen_data;
 pub mod f_drawers;
-pub mod t_drawable;
\ No newline at end of file
+pub mod t_drawable;
+pub mod c_viewport;
+pub mod c_particle_effect;
+pub mod c_particle_system;
+pub mod c_frame_recorder;
+pub mod c_rasterizer;
+pub mod e_render_layer;
+pub mod c_background;
+pub mod c_transition;
+
+#[cfg(test)]
+mod golden_tests;
+#[cfg(test)]
+mod raster_bench;
\ No newline at end of file
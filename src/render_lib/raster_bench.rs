@@ -0,0 +1,99 @@
+﻿//! Rasterizer and blit benchmarks at 1080p and 4K, plus a serial/parallel equivalence check.
+//! Benchmarks are ignored by default: `cargo test --release raster_bench -- --ignored --nocapture`
+
+use std::time::Instant;
+use vek::Vec2;
+use crate::render_lib::c_viewport::Viewport;
+use crate::render_lib::f_drawers::draw_line_thick;
+use crate::render_lib::t_screen_data::Screen;
+
+const SIZES: [(&str, usize, usize); 2] = [("1080p", 1920, 1080), ("4K", 3840, 2160)];
+
+/// Small deterministic generator so every run draws the same frame.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> f32 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+/// Roughly what a busy frame looks like: short mesh-like segments spread over the screen.
+fn queue_scene(screen: &mut Screen, lines: usize, seed: u64) {
+    let mut rng = Lcg(seed);
+    let w = screen.width() as f32;
+    let h = screen.height() as f32;
+
+    for i in 0..lines {
+        let x = rng.next() * w;
+        let y = rng.next() * h;
+        let dx = (rng.next() - 0.5) * 160.0;
+        let dy = (rng.next() - 0.5) * 160.0;
+        let color = 0xFFFFFF - (i as u32 & 0xFF);
+        draw_line_thick(screen, x, y, x + dx, y + dy, 1 + (i % 3) as i32, color);
+    }
+}
+
+fn time_ms(iterations: u32, mut f: impl FnMut()) -> f32 {
+    f();
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    start.elapsed().as_secs_f32() * 1000.0 / iterations as f32
+}
+
+#[test]
+fn raster_parallel_matches_serial() {
+    let mut serial = Screen::new(640, 480);
+    serial.set_raster_threads(1);
+    serial.flush();
+    queue_scene(&mut serial, 2000, 7);
+    serial.rasterize();
+
+    let mut parallel = Screen::new(640, 480);
+    parallel.set_raster_threads(4);
+    parallel.flush();
+    queue_scene(&mut parallel, 2000, 7);
+    parallel.rasterize();
+
+    assert!(serial.get_buffer() == parallel.get_buffer());
+}
+
+#[test]
+#[ignore]
+fn raster_bench_lines() {
+    for (name, w, h) in SIZES {
+        for threads in [1, 0] {
+            let mut screen = Screen::new(w, h);
+            screen.set_raster_threads(threads);
+
+            let ms = time_ms(20, || {
+                screen.flush();
+                queue_scene(&mut screen, 5000, 1);
+                screen.rasterize();
+            });
+            let label = if threads == 1 { "serial" } else { "tiled" };
+            println!("raster {name} {label}: {ms:.2} ms/frame");
+        }
+    }
+}
+
+#[test]
+#[ignore]
+fn raster_bench_blit() {
+    for (name, w, h) in SIZES {
+        let screen = Screen::new(w, h);
+        let mut rgba = vec![0u8; w * h * 4];
+
+        let viewport = Viewport::new(Vec2::new(w, h), Vec2::new(w, h));
+        let ms = time_ms(20, || viewport.blit(&mut rgba, &screen));
+        println!("blit {name} 1:1: {ms:.2} ms/frame");
+
+        let small = Screen::new(w / 2, h / 2);
+        let viewport = Viewport::new(Vec2::new(w / 2, h / 2), Vec2::new(w, h));
+        let ms = time_ms(20, || viewport.blit(&mut rgba, &small));
+        println!("blit {name} 2x upscale: {ms:.2} ms/frame");
+    }
+}
@@ -1,29 +1,45 @@
-﻿use egui::vec2;
-use vek::Vec2;
+﻿use vek::Vec2;
+use crate::render_lib::c_rasterizer::{DrawCommand, TileRasterizer};
 
+/// CPU framebuffer. Drawers queue `DrawCommand`s, `rasterize` turns them into pixels.
 pub struct Screen {
     width: usize,
     height: usize,
-    buffer: Vec<u32>
+    buffer: Vec<u32>,
+
+    commands: Vec<DrawCommand>,
+    rasterizer: TileRasterizer,
 }
 
 impl Screen {
     pub fn new(width: usize, height: usize) -> Self {
         Self {
-            width: width,
-            height: height,
-            buffer: vec![0; (width * height)]
+            width,
+            height,
+            buffer: vec![0; width * height],
+            commands: vec![],
+            rasterizer: TileRasterizer::default(),
         }
     }
 
-    pub fn resize(&mut self, w: u32, h: u32) {
-        self.width = w as usize;
-        self.height = h as usize;
-        self.buffer.resize(self.width * self.height, 0);
-    }
-
     pub fn flush(&mut self) {
         self.buffer.fill(15);
+        self.commands.clear();
+    }
+
+    pub fn push_command(&mut self, command: DrawCommand) {
+        self.commands.push(command);
+    }
+
+    /// Draws all queued commands into the buffer, in parallel over screen tiles.
+    pub fn rasterize(&mut self) {
+        self.rasterizer.rasterize(&mut self.buffer, self.width, self.height, &self.commands);
+        self.commands.clear();
+    }
+
+    /// Worker threads for `rasterize`, `0` uses every available core.
+    pub fn set_raster_threads(&mut self, threads: usize) {
+        self.rasterizer.set_threads(threads);
     }
 
     pub fn width(&self) -> usize { self.width }
@@ -38,4 +54,4 @@ impl Screen {
     pub fn get_buffer(&self) -> &[u32] {
         &self.buffer
     }
-}
\ No newline at end of file
+}
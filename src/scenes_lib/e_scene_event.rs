@@ -1,14 +1,22 @@
-﻿use vek::Vec2;
+﻿use std::rc::Rc;
+use vek::Vec2;
 use crate::classes::t_entity::Entity;
 use crate::collisions_lib::e_col_layers::ColLayer;
+use crate::mesh_lib::c_mesh::Mesh;
+use crate::render_lib::c_particle_effect::ParticleEffect;
+use crate::transform_lib::c_transform::Transform;
 
 pub enum SceneEvent{
     None,
     SpawnEntity(Box<dyn Entity>),
     DestroyEntity(u32),
     Collision { a: u32, b: u32 },
-    DemolishAsteroid{pos: Vec2<f32>, scale: f32, id: u32},
-    PlayerDeath,
+    /// `impact` is in world space, `transform` is the asteroid transform at the moment of the hit,
+    /// `by` is the local player whose bullet broke it.
+    DemolishAsteroid{pos: Vec2<f32>, scale: f32, id: u32, mesh: Rc<Mesh>, transform: Transform, impact: Vec2<f32>, by: Option<usize>},
+    /// Local player index of the ship that was hit and of the player whose bullet hit it.
+    PlayerDeath{player: usize, by: Option<usize>},
     SpawnDebris(Vec2<f32>),
     Shoot(ColLayer),
+    EmitParticles{effect: Rc<ParticleEffect>, count: u32, pos: Vec2<f32>, dir: Vec2<f32>, velocity: Vec2<f32>},
 }
\ No newline at end of file
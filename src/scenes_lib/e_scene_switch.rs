@@ -1,9 +1,33 @@
-﻿use crate::scenes_lib::e_sceneid::SceneId;
+﻿use crate::render_lib::c_transition::Transition;
+use crate::scenes_lib::e_sceneid::SceneId;
 
+/// What the game does with its scene stack after an update or ui pass.
 #[derive(Default, Copy, Clone)]
 pub enum SceneSwitch {
     #[default]
     None,
+    /// Drops every scene and opens this one.
     Switch(SceneId),
+    /// `Switch` with another effect than the default fade.
+    SwitchWith(SceneId, Transition),
+    /// Opens a scene over the current one, which keeps its state until the new one is popped.
+    Push(SceneId),
+    /// Closes the top scene and goes back to the one below.
+    Pop,
+    /// Swaps the top scene for another one, the scenes below stay.
+    Replace(SceneId),
+    /// Closes the top scene and rebuilds the one below from scratch, e.g. restart from the pause menu.
+    Restart,
     Quit
-}
\ No newline at end of file
+}
+
+impl SceneSwitch {
+    /// Effect the game plays over the change. Overlays come and go instantly.
+    pub fn transition(&self) -> Transition {
+        match self {
+            SceneSwitch::Switch(_) | SceneSwitch::Restart => Transition::Fade,
+            SceneSwitch::SwitchWith(_, transition) => *transition,
+            _ => Transition::Cut,
+        }
+    }
+}
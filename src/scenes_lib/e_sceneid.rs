@@ -1,6 +1,42 @@
-﻿#[derive(Clone, Copy, Debug)]
+﻿use crate::classes::e_game_mode::GameMode;
+
+#[derive(Clone, Copy, Debug)]
 pub enum SceneId {
     Menu,
     Game,
-    Test
+    /// Two local players on one keyboard.
+    Coop,
+    /// Local deathmatch with the given number of players.
+    Versus(usize),
+    /// Pause menu over a running game.
+    Pause,
+    /// Controls screen of the menu over a running game.
+    Settings,
+    Test,
+    Editor
+}
+
+impl SceneId {
+    /// Scene that plays a game mode locally.
+    pub fn for_mode(mode: GameMode) -> SceneId {
+        match mode {
+            GameMode::Solo => SceneId::Game,
+            GameMode::Coop => SceneId::Coop,
+            GameMode::Versus { players } => SceneId::Versus(players),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<SceneId> {
+        match name.to_ascii_lowercase().as_str() {
+            "menu" => Some(SceneId::Menu),
+            "game" => Some(SceneId::Game),
+            "coop" => Some(SceneId::Coop),
+            "versus" => Some(SceneId::Versus(2)),
+            "versus3" => Some(SceneId::Versus(3)),
+            "versus4" => Some(SceneId::Versus(4)),
+            "test" => Some(SceneId::Test),
+            "editor" => Some(SceneId::Editor),
+            _ => None,
+        }
+    }
 }
@@ -1,8 +1,13 @@
-﻿use crate::assetsdb_lib::c_assets_db::AssetsDB;
+﻿use std::hash::{DefaultHasher, Hash, Hasher};
+use crate::assetsdb_lib::c_assets_db::AssetsDB;
 use crate::classes::c_input::Input;
+use crate::classes::c_input_bindings::InputBindings;
 use crate::classes::t_entity::Entity;
+use crate::collisions_lib::c_collision_rules::CollisionRules;
 use crate::collisions_lib::col_solver::solve_collision;
 use crate::config_lib::c_config::Config;
+use crate::net_lib::c_lockstep::LockstepSession;
+use crate::render_lib::e_render_layer::RenderLayer;
 use crate::render_lib::t_screen_data::Screen;
 use crate::scenes_lib::e_scene_event::SceneEvent;
 use crate::scenes_lib::e_scene_switch::SceneSwitch;
@@ -21,7 +26,8 @@ pub trait Scene {
 
         let mut commands = vec![];
 
-        let collisions_events = solve_collision(self.get_entities_mut());
+        let rules = self.collision_rules();
+        let collisions_events = solve_collision(self.get_entities_mut(), &rules);
 
 
         commands.extend(collisions_events);
@@ -52,20 +58,75 @@ pub trait Scene {
         }
     }
 
+    fn collision_rules(&self) -> CollisionRules {
+        CollisionRules::default()
+    }
+
     fn custom_events_solve(&mut self, scene_event: &Vec<SceneEvent>, config: &Config, assets_db: &AssetsDB, delta_time: f32) {
 
     }
 
 
     fn render(&mut self, screen: &mut Screen) {
-        for e in self.get_entities_mut().iter_mut() {
-            e.draw(screen);
+        for layer in RenderLayer::ALL {
+            self.render_layer(screen, layer);
         }
     }
+
+    /// Draws the enabled entities of one layer sorted by z-order, ties keep insertion order.
+    fn render_layer(&mut self, screen: &mut Screen, layer: RenderLayer) {
+        let entities = self.get_entities_mut();
+
+        let mut order: Vec<(i32, usize)> = entities.iter()
+            .enumerate()
+            .filter(|(_, e)| e.enabled_renderer() && e.get_render_layer() == layer)
+            .map(|(i, e)| (e.get_z_order(), i))
+            .collect();
+        order.sort_unstable();
+
+        for (_, i) in order {
+            entities[i].draw(screen);
+        }
+    }
+    /// `true` for overlays: the scenes below keep rendering underneath, frozen since only the
+    /// top of the stack updates.
+    fn draws_below(&self) -> bool {
+        false
+    }
+
     fn ui(&mut self, _ctx: &egui::Context) -> SceneSwitch {
         SceneSwitch::None
     }
 
+    /// Bindings of every keyboard player, when the scene edited them since the last call;
+    /// the game saves them to the config.
+    fn take_bindings(&mut self) -> Option<Vec<InputBindings>> {
+        None
+    }
+
+    /// A session the scene connected, the game takes it over and starts the online match.
+    fn take_lockstep(&mut self) -> Option<LockstepSession> {
+        None
+    }
+
+    /// Hash of the simulation state after an update, lockstep peers compare it to detect desyncs.
+    /// Covers layer, position and velocity of every entity, ids differ between peers and are left out.
+    fn state_checksum(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for e in self.get_entities() {
+            e.get_collision_layer().idx().hash(&mut hasher);
+            let (pos, vel) = (*e.get_position(), e.get_velocity());
+            for v in [pos.x, pos.y, vel.x, vel.y] {
+                v.to_bits().hash(&mut hasher);
+            }
+        }
+        self.hash_state(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Scene data beyond the entities that must match between lockstep peers.
+    fn hash_state(&self, _hasher: &mut DefaultHasher) {
+    }
 
 
     fn get_entities(&self) -> &Vec<Box<dyn Entity>>;
@@ -62,6 +62,35 @@ impl Transform {
 
         self.position = p;
     }
+
+    /// The transform itself plus a shifted copy for every screen edge the body of `radius` crosses.
+    /// Up to four copies near a corner, so rendering and collisions both see the wrapped body.
+    pub fn get_wrap_ghosts(&self, radius: f32) -> Vec<Transform> {
+        let size = self.bounds.max - self.bounds.min;
+
+        let mut offsets_x = vec![0.0];
+        if self.position.x - radius < self.bounds.min.x { offsets_x.push(size.x); }
+        if self.position.x + radius > self.bounds.max.x { offsets_x.push(-size.x); }
+
+        let mut offsets_y = vec![0.0];
+        if self.position.y - radius < self.bounds.min.y { offsets_y.push(size.y); }
+        if self.position.y + radius > self.bounds.max.y { offsets_y.push(-size.y); }
+
+        let mut ghosts = Vec::with_capacity(offsets_x.len() * offsets_y.len());
+        for oy in offsets_y.iter() {
+            for ox in offsets_x.iter() {
+                let mut ghost = *self;
+                ghost.position += Vec2::new(*ox, *oy);
+                ghosts.push(ghost);
+            }
+        }
+        ghosts
+    }
+
+    /// Largest axis scale, turns a local radius into a world radius.
+    pub fn get_max_scale(&self) -> f32 {
+        self.scale.x.abs().max(self.scale.y.abs())
+    }
 }
 
 impl Transform {